@@ -13,7 +13,8 @@ fn fgb_to_geojson() -> Result<()> {
     fgb.select_all()?;
     let mut fout = BufWriter::new(tempfile()?); // or File::create("/tmp/countries.json")
     let mut json = GeoJsonWriter::new(&mut fout);
-    fgb.process_features(&mut json)
+    fgb.process_features(&mut json)?;
+    Ok(())
 }
 
 fn fgb_to_geojson_dev_null() -> Result<()> {
@@ -23,7 +24,8 @@ fn fgb_to_geojson_dev_null() -> Result<()> {
     fgb.select_all()?;
     let mut fout = std::io::sink();
     let mut json = GeoJsonWriter::new(&mut fout);
-    fgb.process_features(&mut json)
+    fgb.process_features(&mut json)?;
+    Ok(())
 }
 
 fn fgb_bbox_to_geojson_dev_null() -> Result<()> {
@@ -32,7 +34,8 @@ fn fgb_bbox_to_geojson_dev_null() -> Result<()> {
     fgb.select_bbox(8.8, 47.2, 9.5, 55.3)?;
     let mut fout = std::io::sink();
     let mut json = GeoJsonWriter::new(&mut fout);
-    fgb.process_features(&mut json)
+    fgb.process_features(&mut json)?;
+    Ok(())
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -0,0 +1,191 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use flatgeobuf::*;
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashSet;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+fn random_nodes(num_items: usize) -> Vec<NodeItem> {
+    let unifx = Uniform::from(466379.0..708929.0);
+    let unify = Uniform::from(6096801.0..6322352.0);
+    let mut rng = rand::thread_rng();
+    (0..num_items)
+        .map(|i| {
+            let x = unifx.sample(&mut rng);
+            let y = unify.sample(&mut rng);
+            let mut node = NodeItem::new(x, y, x + 10.0, y + 10.0);
+            node.set_offset(i as u64);
+            node
+        })
+        .collect()
+}
+
+const PAGE_SIZE: u64 = 4096;
+const FEATURE_SIZE: u64 = 200;
+
+/// Reader recording the pages touched
+struct PageCounter<'a> {
+    data: Cursor<&'a [u8]>,
+    pages: HashSet<u64>,
+}
+
+impl Read for PageCounter<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let pos = self.data.position();
+        let n = self.data.read(buf)?;
+        if n > 0 {
+            for page in pos / PAGE_SIZE..=(pos + n as u64 - 1) / PAGE_SIZE {
+                self.pages.insert(page);
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl Seek for PageCounter<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.data.seek(pos)
+    }
+}
+
+/// Dense cluster of points within a world-wide extent
+fn dense_nodes(num_items: usize) -> Vec<NodeItem> {
+    let cluster = Uniform::from(0.0..1.0);
+    let world = Uniform::from(-180.0..180.0);
+    let mut rng = StdRng::seed_from_u64(42);
+    (0..num_items)
+        .map(|i| {
+            let (x, y) = if i % 100 == 0 {
+                (world.sample(&mut rng), world.sample(&mut rng) / 2.0)
+            } else {
+                (cluster.sample(&mut rng), cluster.sample(&mut rng))
+            };
+            NodeItem::new(x, y, x, y)
+        })
+        .collect()
+}
+
+/// Index of nodes sorted by `curve`, with fixed size features
+fn curve_index(nodes: &[NodeItem], curve: SpaceFillingCurve) -> Vec<u8> {
+    let mut nodes = nodes.to_vec();
+    sort_by_curve(&mut nodes, curve);
+    for (i, node) in nodes.iter_mut().enumerate() {
+        node.set_offset(i as u64 * FEATURE_SIZE);
+    }
+    let extent = calc_extent(&nodes);
+    let tree = PackedRTree::build(&nodes, &extent, PackedRTree::DEFAULT_NODE_SIZE).unwrap();
+    let mut data = Vec::with_capacity(tree.size());
+    tree.stream_write(&mut data).unwrap();
+    data
+}
+
+/// Mean number of index and feature pages touched per query
+fn pages_touched(data: &[u8], num_items: usize, queries: &[NodeItem]) -> (f64, f64) {
+    let mut index_pages = 0;
+    let mut feature_pages = 0;
+    for query in queries {
+        let mut counter = PageCounter {
+            data: Cursor::new(data),
+            pages: HashSet::new(),
+        };
+        let results = PackedRTree::stream_search(
+            &mut counter,
+            num_items,
+            PackedRTree::DEFAULT_NODE_SIZE,
+            query.min_x(),
+            query.min_y(),
+            query.max_x(),
+            query.max_y(),
+        )
+        .unwrap();
+        let pages: HashSet<u64> = results
+            .iter()
+            .flat_map(|item| {
+                let offset = item.offset as u64;
+                offset / PAGE_SIZE..=(offset + FEATURE_SIZE - 1) / PAGE_SIZE
+            })
+            .collect();
+        index_pages += counter.pages.len();
+        feature_pages += pages.len();
+    }
+    (
+        index_pages as f64 / queries.len() as f64,
+        feature_pages as f64 / queries.len() as f64,
+    )
+}
+
+fn curve_benchmark(c: &mut Criterion) {
+    let num_items = 1_000_000;
+    let nodes = dense_nodes(num_items);
+    let unif = Uniform::from(0.0..0.99);
+    let mut rng = StdRng::seed_from_u64(7);
+    let queries: Vec<NodeItem> = (0..1000)
+        .map(|_| {
+            let x = unif.sample(&mut rng);
+            let y = unif.sample(&mut rng);
+            NodeItem::new(x, y, x + 0.01, y + 0.01)
+        })
+        .collect();
+
+    for curve in &[
+        SpaceFillingCurve::Hilbert16,
+        SpaceFillingCurve::Hilbert32,
+        SpaceFillingCurve::ZOrder,
+    ] {
+        let data = curve_index(&nodes, *curve);
+        let (index_pages, feature_pages) = pages_touched(&data, num_items, &queries);
+        println!(
+            "{:?}: {:.1} index pages, {:.1} feature pages per query",
+            curve, index_pages, feature_pages
+        );
+        c.bench_function(&format!("curve_search_{:?}", curve), |b| {
+            b.iter(|| {
+                for query in &queries[..100] {
+                    let _ = PackedRTree::stream_search(
+                        &mut Cursor::new(&data),
+                        num_items,
+                        PackedRTree::DEFAULT_NODE_SIZE,
+                        query.min_x(),
+                        query.min_y(),
+                        query.max_x(),
+                        query.max_y(),
+                    );
+                }
+            })
+        });
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let nodes = random_nodes(1_000_000);
+    let mut sorted = nodes.clone();
+    hilbert_sort(&mut sorted);
+    let extent = calc_extent(&sorted);
+
+    c.bench_function("hilbert_sort", |b| {
+        b.iter(|| {
+            let mut items = nodes.clone();
+            hilbert_sort(&mut items);
+        })
+    });
+    c.bench_function("index_build", |b| {
+        b.iter(|| PackedRTree::build(&sorted, &extent, PackedRTree::DEFAULT_NODE_SIZE))
+    });
+
+    #[cfg(feature = "rayon")]
+    {
+        c.bench_function("par_hilbert_sort", |b| {
+            b.iter(|| {
+                let mut items = nodes.clone();
+                par_hilbert_sort(&mut items);
+            })
+        });
+        c.bench_function("par_index_build", |b| {
+            b.iter(|| PackedRTree::par_build(&sorted, &extent, PackedRTree::DEFAULT_NODE_SIZE))
+        });
+    }
+}
+
+criterion_group!(name=benches; config=Criterion::default().sample_size(10); targets=criterion_benchmark, curve_benchmark);
+criterion_main!(benches);
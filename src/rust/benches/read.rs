@@ -3,7 +3,7 @@ use flatgeobuf::*;
 use geozero::error::Result;
 use geozero::GeomProcessor;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, Cursor};
 
 struct NullReader;
 impl GeomProcessor for NullReader {}
@@ -23,33 +23,72 @@ fn read_fgb() -> Result<()> {
     Ok(())
 }
 
-// fn read_header(fname: &str) -> Result<(File, FgbReader)> {
-//     let fin = File::open(fname)?;
-//     let mut filein = BufReader::new(fin);
-//     let fgb = FgbReader::open(&mut filein)?;
-//     Ok((fin, fgb))
-// }
+fn select_bbox(fname: &str) -> Result<()> {
+    let mut filein = BufReader::new(File::open(fname)?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    let _count = fgb.select_bbox(8.8, 47.2, 9.5, 55.3)?;
+    Ok(())
+}
 
-// fn select_bbox(fgb: FgbReader) -> Result<()> {
-//     let _count = fgb.select_bbox(8.8, 47.2, 9.5, 55.3)?;
-//     Ok(())
-// }
+fn index_data(num_items: usize) -> Result<Vec<u8>> {
+    let mut nodes = Vec::with_capacity(num_items);
+    for i in 0..num_items {
+        let x = (i % 1000) as f64;
+        let y = (i / 1000) as f64;
+        let mut node = NodeItem::new(x, y, x + 1.0, y + 1.0);
+        node.set_offset(i as u64);
+        nodes.push(node);
+    }
+    let extent = calc_extent(&nodes);
+    let tree = PackedRTree::build(&nodes, &extent, PackedRTree::DEFAULT_NODE_SIZE)?;
+    let mut data = Vec::with_capacity(tree.size());
+    tree.stream_write(&mut data)?;
+    Ok(data)
+}
 
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("read_fgb", |b| b.iter(|| read_fgb()));
-    // c.bench_function("select_bbox", move |b| {
-    //     b.iter_with_setup(
-    //         || read_header("../../test/data/countries.fgb").unwrap(),
-    //         |(mut filein, fgb)| select_bbox(fgb),
-    //     )
-    // });
-    // c.bench_function("select_bbox_big_index", move |b| {
-    //     b.iter_with_setup(
-    //         || read_header("../../test/data/osm/osm-buildings-ch.fgb").unwrap(),
-    //         // 2'396'905 features (8.522086, 47.363333, 8.553521, 47.376020)
-    //         |(mut filein, hreader)| select_bbox(&mut filein, hreader.header()),
-    //     )
-    // });
+    c.bench_function("select_bbox", |b| {
+        b.iter(|| select_bbox("../../test/data/countries.fgb"))
+    });
+
+    // Index (de)serialization
+    let num_items = 1_000_000;
+    let data = index_data(num_items).unwrap();
+    c.bench_function("index_read", |b| {
+        b.iter(|| {
+            PackedRTree::from_buf(
+                &mut Cursor::new(&data),
+                num_items,
+                PackedRTree::DEFAULT_NODE_SIZE,
+            )
+        })
+    });
+    let tree = PackedRTree::from_buf(
+        &mut Cursor::new(&data),
+        num_items,
+        PackedRTree::DEFAULT_NODE_SIZE,
+    )
+    .unwrap();
+    c.bench_function("index_write", |b| {
+        b.iter(|| {
+            let mut out = Vec::with_capacity(data.len());
+            tree.stream_write(&mut out)
+        })
+    });
+    c.bench_function("index_stream_search", |b| {
+        b.iter(|| {
+            PackedRTree::stream_search(
+                &mut Cursor::new(&data),
+                num_items,
+                PackedRTree::DEFAULT_NODE_SIZE,
+                100.0,
+                100.0,
+                200.0,
+                200.0,
+            )
+        })
+    });
 }
 
 criterion_group!(name=benches; config=Criterion::default().sample_size(10); targets=criterion_benchmark);
@@ -4,7 +4,10 @@ use flatgeobuf::*;
 use libfuzzer_sys::fuzz_target;
 
 fuzz_target!(|data: &[u8]| {
-    let feature = get_root_as_feature(data);
+    let feature = match size_prefixed_root_as_feature(data) {
+        Ok(feature) => feature,
+        Err(_) => return,
+    };
     let _ = feature.geometry();
     let _ = match feature.properties() {
         Some(n) => n,
@@ -0,0 +1,240 @@
+use crate::buffer_check::{check_header, verify_feature};
+use crate::error::{Error, Result};
+use crate::header_generated::flat_geobuf::*;
+use crate::packed_r_tree::{self, PackedRTree};
+use crate::properties_reader::FgbFeature;
+use crate::version::Version;
+use crate::{HEADER_MAX_BUFFER_SIZE, MAGIC_BYTES};
+use geozero::FeatureProcessor;
+use std::io::{self, SeekFrom};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+/// FlatGeobuf dataset reader for async data streams
+pub struct AsyncFgbReader<R> {
+    reader: R,
+    // feature reading requires header access, therefore
+    // header_buf is included in the FgbFeature struct.
+    fbs: FgbFeature,
+    /// File offset of feature section base
+    feature_base: u64,
+    /// Selected features or None if no bbox filter
+    item_filter: Option<Vec<packed_r_tree::SearchResultItem>>,
+    /// Number of selected features
+    count: usize,
+    /// Current feature number
+    feat_no: usize,
+    /// Verify feature buffers
+    verify: bool,
+    /// Format version of the file
+    version: Version,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncFgbReader<R> {
+    /// Open dataset by reading the header information
+    pub async fn open(mut reader: R) -> Result<Self> {
+        let mut magic_buf: [u8; 8] = [0; 8];
+        reader.read_exact(&mut magic_buf).await?;
+        let version = Version::from_magic_bytes(&magic_buf)?;
+
+        let mut size_buf: [u8; 4] = [0; 4];
+        reader.read_exact(&mut size_buf).await?;
+        let header_size = u32::from_le_bytes(size_buf) as usize;
+        if header_size > HEADER_MAX_BUFFER_SIZE || header_size < 8 {
+            // minimum size check avoids panic in FlatBuffers header decoding
+            return Err(Error::HeaderSize {
+                size: header_size,
+                max: HEADER_MAX_BUFFER_SIZE,
+            });
+        }
+
+        // header buffer including size prefix
+        let mut header_buf = vec![0; 4 + header_size];
+        header_buf[..4].copy_from_slice(&size_buf);
+        reader.read_exact(&mut header_buf[4..]).await?;
+        check_header(&header_buf)?;
+
+        Ok(AsyncFgbReader {
+            reader,
+            fbs: FgbFeature {
+                header_buf,
+                feature_buf: Vec::new(),
+            },
+            feature_base: 0,
+            item_filter: None,
+            count: 0,
+            feat_no: 0,
+            verify: true,
+            version,
+        })
+    }
+    /// Enable or disable verification of feature buffers (enabled by default).
+    /// The header is always verified, because it is read by `open` before verification
+    /// can be disabled. Its verification cost is negligible compared to reading features.
+    ///
+    /// # Safety
+    ///
+    /// Features are accessed with the unchecked flatbuffers accessors, so disabling
+    /// verification is only sound for trusted data. Corrupt features can cause panics
+    /// or undefined behaviour.
+    pub unsafe fn set_verify(&mut self, verify: bool) {
+        self.verify = verify;
+    }
+    /// Header information
+    pub fn header(&self) -> Header<'_> {
+        self.fbs.header()
+    }
+    /// Format version of the file
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    fn header_len(&self) -> u64 {
+        (MAGIC_BYTES.len() + self.fbs.header_buf.len()) as u64
+    }
+    /// Select all features.  Returns feature count.
+    pub async fn select_all(&mut self) -> Result<usize> {
+        let header = self.fbs.header();
+        let count = header.features_count() as usize;
+        let index_size = self.version.index_size(count, header.index_node_size())?;
+        // Skip index
+        let feature_base = self.header_len() + index_size as u64;
+        self.feature_base = self.reader.seek(SeekFrom::Start(feature_base)).await?;
+        self.count = count;
+        self.item_filter = None;
+        self.feat_no = 0;
+        Ok(count)
+    }
+    /// Select features within a bounding box. Returns count of selected features.
+    pub async fn select_bbox(
+        &mut self,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> Result<usize> {
+        // Read R-Tree index and build filter for features within bbox
+        let index_base = self.header_len();
+        self.reader.seek(SeekFrom::Start(index_base)).await?;
+        let header = self.fbs.header();
+        let count = header.features_count() as usize;
+        let node_size = header.index_node_size();
+        if node_size == 0 {
+            return Err(Error::NoIndex);
+        }
+        let mut list = PackedRTree::async_stream_search(
+            &mut self.reader,
+            count,
+            node_size,
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        )
+        .await?;
+        self.feature_base = self.reader.seek(SeekFrom::Current(0)).await?;
+        if self.version.has_feature_offsets() {
+            // resolve feature numbers with the offsets table following the index
+            let table_base = self.feature_base;
+            for item in &mut list {
+                item.offset = self.read_feature_offset(table_base, item.offset).await? as usize;
+            }
+            self.feature_base += count as u64 * 8;
+        }
+        self.count = list.len();
+        self.item_filter = Some(list);
+        self.feat_no = 0;
+        Ok(self.count)
+    }
+    /// Number of selected features
+    pub fn features_count(&self) -> usize {
+        self.count
+    }
+    /// Read next feature
+    pub async fn next(&mut self) -> Result<Option<&FgbFeature>> {
+        if self.feat_no >= self.count {
+            return Ok(None);
+        }
+        let mut feature = self.feat_no;
+        if let Some(filter) = &self.item_filter {
+            let item = &filter[self.feat_no];
+            feature = item.index;
+            self.reader
+                .seek(SeekFrom::Start(self.feature_base + item.offset as u64))
+                .await?;
+        }
+        self.feat_no += 1;
+        let mut size_buf: [u8; 4] = [0; 4];
+        let n = read_up_to(&mut self.reader, &mut size_buf).await?;
+        if n < size_buf.len() {
+            return Err(Error::TruncatedFeature {
+                feature,
+                offset: self.feature_start(n).await?,
+                expected: size_buf.len(),
+                actual: n,
+            });
+        }
+        let feature_size = u32::from_le_bytes(size_buf) as usize;
+        // feature buffer including size prefix
+        self.fbs.feature_buf.resize(4 + feature_size, 0);
+        self.fbs.feature_buf[..4].copy_from_slice(&size_buf);
+        let n = read_up_to(&mut self.reader, &mut self.fbs.feature_buf[4..]).await?;
+        if n < feature_size {
+            return Err(Error::TruncatedFeature {
+                feature,
+                offset: self.feature_start(4 + n).await?,
+                expected: 4 + feature_size,
+                actual: 4 + n,
+            });
+        }
+        if self.verify {
+            if let Err(message) = verify_feature(&self.fbs.feature_buf) {
+                return Err(Error::InvalidFeature {
+                    feature,
+                    offset: self.feature_start(4 + feature_size).await?,
+                    message,
+                });
+            }
+        }
+        Ok(Some(&self.fbs))
+    }
+    /// File offset of the current feature, after reading `len` bytes of it
+    async fn feature_start(&mut self, len: usize) -> Result<u64> {
+        let pos = self.reader.seek(SeekFrom::Current(0)).await?;
+        Ok(pos - len as u64)
+    }
+    /// Read offset of feature number `feature` from version 2 offsets table
+    async fn read_feature_offset(&mut self, table_base: u64, feature: usize) -> Result<u64> {
+        let mut buf = [0; 8];
+        self.reader
+            .seek(SeekFrom::Start(table_base + feature as u64 * 8))
+            .await?;
+        self.reader.read_exact(&mut buf).await?;
+        Ok(u64::from_le_bytes(buf))
+    }
+    /// Return current feature
+    pub fn cur_feature(&self) -> &FgbFeature {
+        &self.fbs
+    }
+    /// Read and process all selected features
+    pub async fn process_features<W: FeatureProcessor>(&mut self, out: &mut W) -> Result<()> {
+        out.dataset_begin(self.fbs.header().name())?;
+        let mut cnt = 0;
+        while let Some(feature) = self.next().await? {
+            feature.process(out, cnt)?;
+            cnt += 1;
+        }
+        out.dataset_end()?;
+        Ok(())
+    }
+}
+
+/// Read into `buf` until it is full or the end of data is reached. Returns the number of bytes read.
+async fn read_up_to<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]).await? {
+            0 => break,
+            n => len += n,
+        }
+    }
+    Ok(len)
+}
@@ -0,0 +1,80 @@
+//! Verification of FlatGeobuf flatbuffers with the verifier generated by flatc.
+//!
+//! Buffers include their 4 byte size prefix. FlatGeobuf writers align tables and vectors
+//! relative to the size prefix, and the verifier checks alignment relative to the start of
+//! the buffer, so verifying a buffer without its prefix fails for 8 byte aligned fields.
+//! Verified buffers are accessed with the `_unchecked` root functions.
+
+use crate::error::{Error, Result as FgbResult};
+use crate::feature_generated::flat_geobuf::{size_prefixed_root_as_feature, Feature};
+use crate::header_generated::flat_geobuf::{size_prefixed_root_as_header, Header};
+
+/// Verify size prefixed header buffer
+pub(crate) fn verify_header(buf: &[u8]) -> Result<Header<'_>, String> {
+    size_prefixed_root_as_header(buf).map_err(|e| format!("Invalid header: {}", e))
+}
+
+/// Verify size prefixed feature buffer
+pub(crate) fn verify_feature(buf: &[u8]) -> Result<Feature<'_>, String> {
+    size_prefixed_root_as_feature(buf).map_err(|e| format!("Invalid feature: {}", e))
+}
+
+/// Verify size prefixed header buffer as reader error
+pub(crate) fn check_header(buf: &[u8]) -> FgbResult<()> {
+    verify_header(buf).map(|_| ()).map_err(Error::InvalidHeader)
+}
+
+#[cfg(test)]
+use crate::feature_generated::flat_geobuf::FeatureArgs;
+#[cfg(test)]
+use crate::owned_geometry::{Coordinate, OwnedGeometry};
+
+#[cfg(test)]
+fn countries_header() -> Vec<u8> {
+    let data = std::fs::read("../../test/data/countries.fgb").unwrap();
+    let header_size = u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize;
+    data[8..12 + header_size].to_vec()
+}
+
+#[test]
+fn verify_valid_buffers() {
+    assert!(verify_header(&countries_header()).is_ok());
+
+    let mut fbb = flatbuffers::FlatBufferBuilder::new();
+    let geometry = OwnedGeometry::MultiPolygon(vec![
+        vec![vec![Coordinate::xy(0.0, 0.0), Coordinate::xy(1.0, 1.0)]],
+        vec![
+            vec![Coordinate::xy(2.0, 2.0)],
+            vec![Coordinate::xy(3.0, 3.0)],
+        ],
+    ])
+    .to_fbs(&mut fbb);
+    let feature = Feature::create(
+        &mut fbb,
+        &FeatureArgs {
+            geometry: Some(geometry),
+            ..Default::default()
+        },
+    );
+    fbb.finish_size_prefixed(feature, None);
+    assert!(verify_feature(fbb.finished_data()).is_ok());
+}
+
+#[test]
+fn verify_corrupt_buffers() {
+    let countries = countries_header();
+    assert!(verify_header(&countries[..countries.len() / 2]).is_err());
+    assert!(verify_header(&[]).is_err());
+    // root offset out of bounds
+    let mut corrupt = countries.clone();
+    corrupt[6] = 0xff;
+    assert!(verify_header(&corrupt).is_err());
+    // header without size prefix is misaligned
+    assert!(crate::header_generated::flat_geobuf::root_as_header(&countries[4..]).is_err());
+    // single byte changes never panic
+    for pos in 0..countries.len() {
+        let mut corrupt = countries.clone();
+        corrupt[pos] ^= 0xa5;
+        let _ = verify_header(&corrupt);
+    }
+}
@@ -4,6 +4,8 @@ use async_trait::async_trait;
 use geozero::error::Result;
 use geozero::{FeatureProcessor, HttpReader, OpenOpts, ReadSeek, Reader, SelectOpts};
 
+/// geozero `Reader` for FlatGeobuf files.
+/// Errors are `flatgeobuf::Error` converted into `GeozeroError`, see `Error::from_geozero`.
 pub struct Driver<'a>(FgbReader<'a>);
 
 impl<'a> Reader<'a> for Driver<'a> {
@@ -22,10 +24,13 @@ impl<'a> Reader<'a> for Driver<'a> {
     }
 
     fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
-        self.0.process_features(processor)
+        self.0.process_features(processor)?;
+        Ok(())
     }
 }
 
+/// geozero `HttpReader` for FlatGeobuf files.
+/// Errors are `flatgeobuf::Error` converted into `GeozeroError`, see `Error::from_geozero`.
 pub struct HttpDriver(HttpFgbReader);
 
 #[async_trait]
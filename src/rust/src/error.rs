@@ -0,0 +1,205 @@
+//! FlatGeobuf error type.
+
+use crate::version::Version;
+use geozero::error::GeozeroError;
+use std::fmt;
+use std::io;
+
+/// FlatGeobuf error with context about the failing location
+#[derive(Debug)]
+pub enum Error {
+    /// File does not start with the FlatGeobuf magic bytes
+    MagicBytes([u8; 8]),
+    /// FlatGeobuf file with unsupported major version
+    UnsupportedVersion(Version),
+    /// Header size out of valid range
+    HeaderSize {
+        size: usize,
+        max: usize,
+    },
+    /// Header buffer failed verification
+    InvalidHeader(String),
+    /// Feature buffer failed verification
+    InvalidFeature {
+        /// Feature number
+        feature: usize,
+        /// File offset of size prefixed feature
+        offset: u64,
+        message: String,
+    },
+    /// Data ends within a feature
+    TruncatedFeature {
+        /// Feature number
+        feature: usize,
+        /// File offset of size prefixed feature
+        offset: u64,
+        /// Expected number of bytes
+        expected: usize,
+        /// Number of bytes available
+        actual: usize,
+    },
+    /// Dataset has no spatial index
+    NoIndex,
+    /// Index levels are not loaded into memory
+    IndexNotLoaded,
+    /// Index node size below 2
+    IndexNodeSize(u16),
+    /// Index without items
+    IndexEmpty,
+    /// Number of index items too large
+    IndexTooLarge(usize),
+    /// Internal index node references children outside of the next level
+    IndexChildOffset {
+        /// Node index
+        node: usize,
+        /// Child offset read from node
+        offset: u64,
+        /// Valid child range start
+        min: usize,
+        /// Valid child range end (exclusive)
+        max: usize,
+    },
+    /// Sorted run of the external index builder could not be read
+    IndexRun {
+        /// Run number
+        run: usize,
+        /// Number of items read from the run
+        item: usize,
+        message: String,
+    },
+    /// Index level missing while building the levels above
+    IndexLevel(usize),
+    Io(io::Error),
+    Geozero(GeozeroError),
+}
+
+/// Result with FlatGeobuf error
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::MagicBytes(bytes) => write!(f, "invalid magic bytes {:02x?}", bytes),
+            Error::UnsupportedVersion(version) => {
+                write!(f, "unsupported version {}", version.major)
+            }
+            Error::HeaderSize { size, max } => write!(
+                f,
+                "invalid header size {} (expected between 8 and {})",
+                size, max
+            ),
+            Error::InvalidHeader(message) => write!(f, "{}", message),
+            Error::InvalidFeature {
+                feature,
+                offset,
+                message,
+            } => write!(f, "feature {} at offset {}: {}", feature, offset, message),
+            Error::TruncatedFeature {
+                feature,
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "feature {} at offset {} truncated: expected {} bytes, found {}",
+                feature, offset, expected, actual
+            ),
+            Error::NoIndex => write!(f, "dataset has no spatial index"),
+            Error::IndexNotLoaded => write!(f, "spatial index not loaded"),
+            Error::IndexNodeSize(node_size) => {
+                write!(
+                    f,
+                    "invalid index node size {} (expected at least 2)",
+                    node_size
+                )
+            }
+            Error::IndexEmpty => write!(f, "cannot create index without items"),
+            Error::IndexTooLarge(num_items) => {
+                write!(f, "number of index items {} too large", num_items)
+            }
+            Error::IndexChildOffset {
+                node,
+                offset,
+                min,
+                max,
+            } => write!(
+                f,
+                "index node {} references child {} outside of range {}..{}",
+                node, offset, min, max
+            ),
+            Error::IndexRun { run, item, message } => write!(
+                f,
+                "index builder run {} unreadable at item {}: {}",
+                run, item, message
+            ),
+            Error::IndexLevel(level) => write!(f, "index level {} missing", level),
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Geozero(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Geozero(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    /// FlatGeobuf error contained in a `GeozeroError` converted from `Error`
+    pub fn from_geozero(error: &GeozeroError) -> Option<&Error> {
+        match error {
+            GeozeroError::IoError(e) => e.get_ref()?.downcast_ref::<Error>(),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<GeozeroError> for Error {
+    fn from(error: GeozeroError) -> Self {
+        Error::Geozero(error)
+    }
+}
+
+/// Errors of the FlatGeobuf format are returned as `GeozeroError::IoError` with kind
+/// `InvalidData`. The original error is available with `Error::from_geozero`.
+impl From<Error> for GeozeroError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Io(e) => GeozeroError::IoError(e),
+            Error::Geozero(e) => e,
+            e => GeozeroError::IoError(io::Error::new(io::ErrorKind::InvalidData, e)),
+        }
+    }
+}
+
+#[test]
+fn geozero_conversion() {
+    let error = Error::TruncatedFeature {
+        feature: 3,
+        offset: 1024,
+        expected: 100,
+        actual: 42,
+    };
+    let geozero_error = GeozeroError::from(error);
+    match Error::from_geozero(&geozero_error) {
+        Some(Error::TruncatedFeature {
+            feature, actual, ..
+        }) => assert_eq!((*feature, *actual), (3, 42)),
+        _ => panic!("FlatGeobuf error expected"),
+    }
+
+    let geozero_error = GeozeroError::from(Error::Geozero(GeozeroError::Coord));
+    assert!(matches!(geozero_error, GeozeroError::Coord));
+    assert!(Error::from_geozero(&geozero_error).is_none());
+}
@@ -1,10 +1,12 @@
+use crate::buffer_check::{check_header, verify_feature};
+use crate::error::{Error, Result};
 use crate::header_generated::flat_geobuf::*;
-use crate::packed_r_tree::{self, PackedRTree};
+use crate::packed_r_tree::{self, dataset_index_size, IndexSearch, NodeItem, PackedRTree};
 use crate::properties_reader::FgbFeature;
+use crate::version::Version;
 use crate::{HEADER_MAX_BUFFER_SIZE, MAGIC_BYTES};
-use geozero::error::{GeozeroError, Result};
 use geozero::{FeatureProcessor, ReadSeek};
-use std::io::SeekFrom;
+use std::io::{self, Read, SeekFrom};
 
 /// FlatGeobuf dataset reader
 pub struct FgbReader<'a> {
@@ -16,10 +18,16 @@ pub struct FgbReader<'a> {
     feature_base: u64,
     /// Selected features or None if no bbox filter
     item_filter: Option<Vec<packed_r_tree::SearchResultItem>>,
+    /// Index search in progress for lazy bbox selection
+    search: Option<IndexSearch>,
     /// Number of selected features
     count: usize,
     /// Current feature number
     feat_no: usize,
+    /// Verify feature buffers
+    verify: bool,
+    /// Format version of the file
+    version: Version,
 }
 
 impl<'a> FgbReader<'a> {
@@ -27,21 +35,24 @@ impl<'a> FgbReader<'a> {
     pub fn open<R: 'a + ReadSeek>(reader: &'a mut R) -> Result<Self> {
         let mut magic_buf: [u8; 8] = [0; 8];
         reader.read_exact(&mut magic_buf)?;
-        if magic_buf != MAGIC_BYTES {
-            return Err(GeozeroError::GeometryFormat);
-        }
+        let version = Version::from_magic_bytes(&magic_buf)?;
 
         let mut size_buf: [u8; 4] = [0; 4];
         reader.read_exact(&mut size_buf)?;
         let header_size = u32::from_le_bytes(size_buf) as usize;
         if header_size > HEADER_MAX_BUFFER_SIZE || header_size < 8 {
             // minimum size check avoids panic in FlatBuffers header decoding
-            return Err(GeozeroError::GeometryFormat);
+            return Err(Error::HeaderSize {
+                size: header_size,
+                max: HEADER_MAX_BUFFER_SIZE,
+            });
         }
 
-        let mut header_buf = Vec::with_capacity(header_size);
-        header_buf.resize(header_size, 0);
-        reader.read_exact(&mut header_buf)?;
+        // header buffer including size prefix
+        let mut header_buf = vec![0; 4 + header_size];
+        header_buf[..4].copy_from_slice(&size_buf);
+        reader.read_exact(&mut header_buf[4..])?;
+        check_header(&header_buf)?;
 
         Ok(FgbReader {
             reader,
@@ -51,64 +62,260 @@ impl<'a> FgbReader<'a> {
             },
             feature_base: 0,
             item_filter: None,
+            search: None,
             count: 0,
             feat_no: 0,
+            verify: true,
+            version,
         })
     }
+    /// Enable or disable verification of feature buffers (enabled by default).
+    /// The header is always verified, because it is read by `open` before verification
+    /// can be disabled. Its verification cost is negligible compared to reading features.
+    ///
+    /// # Safety
+    ///
+    /// Features are accessed with the unchecked flatbuffers accessors, so disabling
+    /// verification is only sound for trusted data. Corrupt features can cause panics
+    /// or undefined behaviour.
+    pub unsafe fn set_verify(&mut self, verify: bool) {
+        self.verify = verify;
+    }
     /// Header information
-    pub fn header(&self) -> Header {
+    pub fn header(&self) -> Header<'_> {
         self.fbs.header()
     }
+    /// Format version of the file
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    /// Dataset extent from header envelope, or from index root node
+    /// if the header has no envelope. Returns `None` without index.
+    pub fn envelope(&mut self) -> Result<Option<NodeItem>> {
+        let header = self.fbs.header();
+        if let Some(envelope) = header.envelope() {
+            if envelope.len() >= 4 {
+                return Ok(Some(NodeItem::new(
+                    envelope.get(0),
+                    envelope.get(1),
+                    envelope.get(2),
+                    envelope.get(3),
+                )));
+            }
+        }
+        if header.index_node_size() == 0 || header.features_count() == 0 {
+            return Ok(None);
+        }
+        let pos = self.reader.seek(SeekFrom::Current(0))?;
+        self.reader.seek(SeekFrom::Start(self.index_base()))?;
+        let extent = PackedRTree::read_extent(&mut self.reader);
+        self.reader.seek(SeekFrom::Start(pos))?;
+        Ok(Some(extent?))
+    }
+    /// Read the complete index into memory
+    pub fn read_index(&mut self) -> Result<PackedRTree> {
+        let header = self.fbs.header();
+        let features_count = header.features_count() as usize;
+        let index_node_size = header.index_node_size();
+        if index_node_size == 0 || features_count == 0 {
+            return Err(Error::NoIndex);
+        }
+        let pos = self.reader.seek(SeekFrom::Current(0))?;
+        self.reader.seek(SeekFrom::Start(self.index_base()))?;
+        let index = PackedRTree::from_buf(&mut self.reader, features_count, index_node_size);
+        self.reader.seek(SeekFrom::Start(pos))?;
+        index
+    }
+    /// Read feature number `feature` at byte offset in feature data section,
+    /// independent of the selection
+    pub(crate) fn read_feature_at(&mut self, feature: usize, offset: usize) -> Result<&FgbFeature> {
+        let header = self.fbs.header();
+        let count = header.features_count() as usize;
+        let node_size = header.index_node_size();
+        let feature_base = self.index_base() + self.version.index_size(count, node_size)? as u64;
+        let mut offset = offset as u64;
+        if self.version.has_feature_offsets() {
+            // offset of index leaf is the feature number
+            let table_base = self.index_base() + dataset_index_size(count, node_size)? as u64;
+            offset = self.read_feature_offset(table_base, offset as usize)?;
+        }
+        self.reader.seek(SeekFrom::Start(feature_base + offset))?;
+        self.read_feature(feature)?;
+        Ok(&self.fbs)
+    }
+    pub(crate) fn reader_position(&mut self) -> Result<u64> {
+        Ok(self.reader.seek(SeekFrom::Current(0))?)
+    }
+    pub(crate) fn seek_reader(&mut self, pos: SeekFrom) -> Result<u64> {
+        Ok(self.reader.seek(pos)?)
+    }
+    fn index_base(&self) -> u64 {
+        (MAGIC_BYTES.len() + self.fbs.header_buf.len()) as u64
+    }
     /// Select all features.  Returns feature count.
     pub fn select_all(&mut self) -> Result<usize> {
         let header = self.fbs.header();
         let count = header.features_count() as usize;
-        let index_size = PackedRTree::index_size(count, header.index_node_size());
+        let index_size = self.version.index_size(count, header.index_node_size())?;
         // Skip index
-        self.feature_base = self.reader.seek(SeekFrom::Current(index_size as i64))?;
+        let feature_base = self.index_base() + index_size as u64;
+        self.feature_base = self.reader.seek(SeekFrom::Start(feature_base))?;
         self.count = count;
+        self.item_filter = None;
+        self.search = None;
+        self.feat_no = 0;
         Ok(count)
     }
     /// Select features within a bounding box. Returns count of selected features.
     pub fn select_bbox(&mut self, min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Result<usize> {
         // Read R-Tree index and build filter for features within bbox
         let header = self.fbs.header();
-        let list = PackedRTree::stream_search(
+        let count = header.features_count() as usize;
+        let node_size = header.index_node_size();
+        if node_size == 0 {
+            return Err(Error::NoIndex);
+        }
+        self.reader.seek(SeekFrom::Start(self.index_base()))?;
+        let mut list = PackedRTree::stream_search(
             &mut self.reader,
-            header.features_count() as usize,
-            PackedRTree::DEFAULT_NODE_SIZE,
+            count,
+            node_size,
             min_x,
             min_y,
             max_x,
             max_y,
         )?;
         self.feature_base = self.reader.seek(SeekFrom::Current(0))?;
+        if self.version.has_feature_offsets() {
+            // resolve feature numbers with the offsets table following the index
+            let table_base = self.feature_base;
+            for item in &mut list {
+                item.offset = self.read_feature_offset(table_base, item.offset)? as usize;
+            }
+            self.feature_base += count as u64 * 8;
+        }
         self.count = list.len();
         self.item_filter = Some(list);
+        self.search = None;
+        self.feat_no = 0;
         Ok(self.count)
     }
-    /// Number of selected features
+    /// Select features within a bounding box without searching the whole index in advance.
+    /// The index is traversed depth-first while features are read, so the first feature is
+    /// available after reading one node per index level, and only the nodes along the current
+    /// path are kept in memory. Index reads are not sequential, unlike `select_bbox`.
+    pub fn select_bbox_lazy(
+        &mut self,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> Result<()> {
+        let header = self.fbs.header();
+        if header.index_node_size() == 0 {
+            return Err(Error::NoIndex);
+        }
+        let search = IndexSearch::depth_first(
+            self.index_base(),
+            header.features_count() as usize,
+            header.index_node_size(),
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        )?;
+        self.feature_base = search.index_end();
+        if self.version.has_feature_offsets() {
+            self.feature_base += header.features_count() * 8;
+        }
+        self.search = Some(search);
+        self.item_filter = None;
+        self.count = 0;
+        self.feat_no = 0;
+        Ok(())
+    }
+    /// Number of selected features.
+    /// Always 0 for `select_bbox_lazy`, where the count is not known in advance.
     pub fn features_count(&self) -> usize {
         self.count
     }
     /// Read next feature
     pub fn next(&mut self) -> Result<Option<&FgbFeature>> {
-        if self.feat_no >= self.count {
+        let mut feature = self.feat_no;
+        if let Some(search) = &mut self.search {
+            let table_base = search.index_end();
+            let mut item = match search.next_item(&mut self.reader)? {
+                Some(item) => item,
+                None => return Ok(None),
+            };
+            feature = item.index;
+            if self.version.has_feature_offsets() {
+                item.offset = self.read_feature_offset(table_base, item.offset)? as usize;
+            }
+            self.reader
+                .seek(SeekFrom::Start(self.feature_base + item.offset as u64))?;
+        } else if self.feat_no >= self.count {
             return Ok(None);
-        }
-        if let Some(filter) = &self.item_filter {
+        } else if let Some(filter) = &self.item_filter {
             let item = &filter[self.feat_no];
+            feature = item.index;
             self.reader
                 .seek(SeekFrom::Start(self.feature_base + item.offset as u64))?;
         }
         self.feat_no += 1;
-        let mut size_buf: [u8; 4] = [0; 4];
-        self.reader.read_exact(&mut size_buf)?;
-        let feature_size = u32::from_le_bytes(size_buf);
-        self.fbs.feature_buf.resize(feature_size as usize, 0);
-        self.reader.read_exact(&mut self.fbs.feature_buf)?;
+        self.read_feature(feature)?;
         Ok(Some(&self.fbs))
     }
+    /// Read size prefixed feature number `feature` at current position
+    fn read_feature(&mut self, feature: usize) -> Result<()> {
+        let mut size_buf: [u8; 4] = [0; 4];
+        let n = read_up_to(&mut self.reader, &mut size_buf)?;
+        if n < size_buf.len() {
+            return Err(Error::TruncatedFeature {
+                feature,
+                offset: self.feature_start(n)?,
+                expected: size_buf.len(),
+                actual: n,
+            });
+        }
+        let feature_size = u32::from_le_bytes(size_buf) as usize;
+        // feature buffer including size prefix
+        self.fbs.feature_buf.resize(4 + feature_size, 0);
+        self.fbs.feature_buf[..4].copy_from_slice(&size_buf);
+        let n = read_up_to(&mut self.reader, &mut self.fbs.feature_buf[4..])?;
+        if n < feature_size {
+            return Err(Error::TruncatedFeature {
+                feature,
+                offset: self.feature_start(4 + n)?,
+                expected: 4 + feature_size,
+                actual: 4 + n,
+            });
+        }
+        if self.verify {
+            if let Err(message) = verify_feature(&self.fbs.feature_buf) {
+                return Err(Error::InvalidFeature {
+                    feature,
+                    offset: self.feature_start(4 + feature_size)?,
+                    message,
+                });
+            }
+        }
+        Ok(())
+    }
+    /// File offset of the feature, after reading `len` bytes of it.
+    /// Only used for errors, because seeking discards buffered data.
+    fn feature_start(&mut self, len: usize) -> Result<u64> {
+        let pos = self.reader.seek(SeekFrom::Current(0))?;
+        Ok(pos - len as u64)
+    }
+    /// Read offset of feature number `feature` from version 2 offsets table
+    fn read_feature_offset(&mut self, table_base: u64, feature: usize) -> Result<u64> {
+        let mut buf = [0; 8];
+        self.reader
+            .seek(SeekFrom::Start(table_base + feature as u64 * 8))?;
+        self.reader.read_exact(&mut buf)?;
+        Ok(u64::from_le_bytes(buf))
+    }
     /// Return current feature
     pub fn cur_feature(&self) -> &FgbFeature {
         &self.fbs
@@ -121,7 +328,131 @@ impl<'a> FgbReader<'a> {
             feature.process(out, cnt)?;
             cnt += 1;
         }
-        out.dataset_end()
+        out.dataset_end()?;
+        Ok(())
+    }
+}
+
+/// Read into `buf` until it is full or the end of data is reached. Returns the number of bytes read.
+fn read_up_to(reader: &mut dyn Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(len)
+}
+
+#[cfg(feature = "rayon")]
+mod parallel {
+    use super::*;
+    use geozero::error::Result as GeozeroResult;
+    use rayon::prelude::*;
+    use std::sync::Mutex;
+
+    /// Number of features read before decoding them in parallel
+    const BATCH_SIZE: usize = 4096;
+
+    impl FgbReader<'_> {
+        /// Read selected features and decode them in parallel on the rayon thread pool.
+        /// Results are returned in feature order.
+        pub fn par_map_features<T, F>(&mut self, f: F) -> Result<Vec<T>>
+        where
+            T: Send,
+            F: Fn(&FgbFeature, u64) -> GeozeroResult<T> + Sync + Send,
+        {
+            let mut results = Vec::with_capacity(self.count.saturating_sub(self.feat_no));
+            loop {
+                let first = self.feat_no as u64;
+                let batch = self.read_batch()?;
+                if batch.is_empty() {
+                    break;
+                }
+                let header_buf = &self.fbs.header_buf;
+                let batch_results = batch
+                    .into_par_iter()
+                    .enumerate()
+                    .map_init(
+                        || FgbFeature {
+                            header_buf: header_buf.clone(),
+                            feature_buf: Vec::new(),
+                        },
+                        |fbs, (i, feature_buf)| {
+                            fbs.feature_buf = feature_buf;
+                            f(fbs, first + i as u64)
+                        },
+                    )
+                    .collect::<GeozeroResult<Vec<T>>>()?;
+                results.extend(batch_results);
+            }
+            Ok(results)
+        }
+
+        /// Read selected features and process them in parallel on the rayon thread pool.
+        /// Each thread processes its share of the features with its own processor created
+        /// by `init`. Returns the processors of all participating threads.
+        pub fn par_process_features<W, F>(&mut self, init: F) -> Result<Vec<W>>
+        where
+            W: FeatureProcessor + Send,
+            F: Fn() -> W + Sync + Send,
+        {
+            let slots: Vec<Mutex<Option<(FgbFeature, W)>>> = (0..rayon::current_num_threads())
+                .map(|_| Mutex::new(None))
+                .collect();
+            let name = self.header().name().map(|name| name.to_string());
+            loop {
+                let first = self.feat_no as u64;
+                let batch = self.read_batch()?;
+                if batch.is_empty() {
+                    break;
+                }
+                let header_buf = &self.fbs.header_buf;
+                batch.into_par_iter().enumerate().try_for_each(
+                    |(i, feature_buf)| -> GeozeroResult<()> {
+                        let thread_idx = rayon::current_thread_index().unwrap_or(0);
+                        let mut slot = slots[thread_idx % slots.len()].lock().unwrap();
+                        if slot.is_none() {
+                            let mut processor = init();
+                            processor.dataset_begin(name.as_deref())?;
+                            let fbs = FgbFeature {
+                                header_buf: header_buf.clone(),
+                                feature_buf: Vec::new(),
+                            };
+                            *slot = Some((fbs, processor));
+                        }
+                        if let Some((fbs, processor)) = slot.as_mut() {
+                            fbs.feature_buf = feature_buf;
+                            fbs.process(processor, first + i as u64)?;
+                        }
+                        Ok(())
+                    },
+                )?;
+            }
+            let mut processors = Vec::new();
+            for slot in slots {
+                if let Some((_, mut processor)) = slot.into_inner().unwrap() {
+                    processor.dataset_end()?;
+                    processors.push(processor);
+                }
+            }
+            Ok(processors)
+        }
+
+        /// Read buffers of next selected features
+        fn read_batch(&mut self) -> Result<Vec<Vec<u8>>> {
+            let mut batch = Vec::with_capacity(BATCH_SIZE);
+            while batch.len() < BATCH_SIZE {
+                if self.next()?.is_none() {
+                    break;
+                }
+                batch.push(std::mem::take(&mut self.fbs.feature_buf));
+            }
+            Ok(batch)
+        }
     }
 }
 
@@ -0,0 +1,119 @@
+//! Conversion to [geo-types](https://docs.rs/geo-types/) geometries.
+
+use crate::feature_generated::flat_geobuf::*;
+use crate::header_generated::flat_geobuf::*;
+use crate::properties_reader::FgbFeature;
+use geo_types::{
+    Coordinate, LineString, MultiLineString, MultiPoint, MultiPolygon, Point, Polygon,
+};
+use geozero::error::{GeozeroError, Result};
+
+fn coords(geometry: &Geometry, offset: usize, length: usize) -> Result<Vec<Coordinate<f64>>> {
+    let xy = geometry.xy().ok_or(GeozeroError::Coord)?;
+    if xy.len() % 2 != 0 || offset + length > xy.len() {
+        return Err(GeozeroError::Coord);
+    }
+    Ok((offset..offset + length)
+        .step_by(2)
+        .map(|i| Coordinate {
+            x: xy.get(i),
+            y: xy.get(i + 1),
+        })
+        .collect())
+}
+
+/// Coordinate sequences of parts separated by `ends`
+fn parts(geometry: &Geometry) -> Result<Vec<LineString<f64>>> {
+    let len = geometry.xy().ok_or(GeozeroError::Coord)?.len();
+    match geometry.ends() {
+        Some(ends) if ends.len() > 1 => {
+            let mut parts = Vec::with_capacity(ends.len());
+            let mut offset = 0;
+            for i in 0..ends.len() {
+                let end = (ends.get(i) << 1) as usize;
+                if end < offset {
+                    return Err(GeozeroError::GeometryFormat);
+                }
+                parts.push(LineString(coords(geometry, offset, end - offset)?));
+                offset = end;
+            }
+            Ok(parts)
+        }
+        _ => Ok(vec![LineString(coords(geometry, 0, len)?)]),
+    }
+}
+
+fn polygon(geometry: &Geometry) -> Result<Polygon<f64>> {
+    let mut rings = parts(geometry)?;
+    let exterior = rings.remove(0);
+    Ok(Polygon::new(exterior, rings))
+}
+
+fn to_geo(geometry: &Geometry, geometry_type: GeometryType) -> Result<geo_types::Geometry<f64>> {
+    let geom = match geometry_type {
+        GeometryType::Point => {
+            let xy = geometry.xy().ok_or(GeozeroError::Coord)?;
+            if xy.len() < 2 {
+                return Err(GeozeroError::Coord);
+            }
+            Point::new(xy.get(0), xy.get(1)).into()
+        }
+        GeometryType::MultiPoint => {
+            let len = geometry.xy().ok_or(GeozeroError::Coord)?.len();
+            let points = coords(geometry, 0, len)?.into_iter().map(Point).collect();
+            MultiPoint(points).into()
+        }
+        GeometryType::LineString => {
+            let len = geometry.xy().ok_or(GeozeroError::Coord)?.len();
+            LineString(coords(geometry, 0, len)?).into()
+        }
+        GeometryType::MultiLineString => MultiLineString(parts(geometry)?).into(),
+        GeometryType::Polygon => polygon(geometry)?.into(),
+        GeometryType::MultiPolygon => {
+            let parts = geometry.parts().ok_or(GeozeroError::GeometryFormat)?;
+            let mut polygons = Vec::with_capacity(parts.len());
+            for i in 0..parts.len() {
+                polygons.push(polygon(&parts.get(i))?);
+            }
+            MultiPolygon(polygons).into()
+        }
+        GeometryType::GeometryCollection => {
+            let parts = geometry.parts().ok_or(GeozeroError::GeometryFormat)?;
+            let mut geometries = Vec::with_capacity(parts.len());
+            for i in 0..parts.len() {
+                let part = parts.get(i);
+                geometries.push(to_geo(&part, part.type_())?);
+            }
+            geo_types::Geometry::GeometryCollection(geo_types::GeometryCollection(geometries))
+        }
+        _ => {
+            return Err(GeozeroError::Geometry(format!(
+                "Geometry type {:?} not supported by geo-types",
+                geometry_type
+            )))
+        }
+    };
+    Ok(geom)
+}
+
+impl Geometry<'_> {
+    /// Convert to geo-types geometry. Only XY coordinates are converted.
+    /// Curve and surface types are not supported.
+    pub fn to_geo(&self, geometry_type: GeometryType) -> Result<geo_types::Geometry<f64>> {
+        let geometry_type = if geometry_type == GeometryType::Unknown {
+            // per feature geometry type
+            self.type_()
+        } else {
+            geometry_type
+        };
+        to_geo(self, geometry_type)
+    }
+}
+
+impl FgbFeature {
+    /// Feature geometry as geo-types geometry
+    pub fn geometry_geo(&self) -> Result<geo_types::Geometry<f64>> {
+        let geometry = self.geometry().ok_or(GeozeroError::GeometryFormat)?;
+        geometry.to_geo(self.header().geometry_type())
+    }
+}
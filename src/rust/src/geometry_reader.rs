@@ -1,5 +1,7 @@
 use crate::feature_generated::flat_geobuf::*;
 use crate::header_generated::flat_geobuf::*;
+use crate::owned_geometry::{Coordinate, OwnedGeometry};
+use crate::packed_r_tree::NodeItem;
 use geozero::error::{GeozeroError, Result};
 use geozero::GeomProcessor;
 
@@ -400,6 +402,165 @@ fn read_geometry_n<P: GeomProcessor>(
     Ok(())
 }
 
+fn read_owned_coords(geometry: &Geometry, offset: usize, length: usize) -> Result<Vec<Coordinate>> {
+    let xy = geometry.xy().ok_or(GeozeroError::Coord)?;
+    if (offset + length) * 2 > xy.len() {
+        return Err(GeozeroError::Coord);
+    }
+    let (z, m, t, tm) = (geometry.z(), geometry.m(), geometry.t(), geometry.tm());
+    let dim_len = |len: Option<usize>| len.map_or(true, |len| len >= offset + length);
+    if !(dim_len(z.map(|d| d.len()))
+        && dim_len(m.map(|d| d.len()))
+        && dim_len(t.map(|d| d.len()))
+        && dim_len(tm.map(|d| d.len())))
+    {
+        return Err(GeozeroError::Coord);
+    }
+    Ok((offset..offset + length)
+        .map(|i| Coordinate {
+            x: xy.get(i * 2),
+            y: xy.get(i * 2 + 1),
+            z: z.map(|dim| dim.get(i)),
+            m: m.map(|dim| dim.get(i)),
+            t: t.map(|dim| dim.get(i)),
+            tm: tm.map(|dim| dim.get(i)),
+        })
+        .collect())
+}
+
+fn read_owned_line(geometry: &Geometry) -> Result<Vec<Coordinate>> {
+    let len = geometry.xy().ok_or(GeozeroError::Coord)?.len() / 2;
+    read_owned_coords(geometry, 0, len)
+}
+
+/// Read coordinate sequences separated by `ends`
+fn read_owned_rings(geometry: &Geometry) -> Result<Vec<Vec<Coordinate>>> {
+    let len = geometry.xy().ok_or(GeozeroError::Coord)?.len() / 2;
+    match geometry.ends() {
+        Some(ends) if ends.len() > 1 => {
+            let mut rings = Vec::with_capacity(ends.len());
+            let mut offset = 0;
+            for i in 0..ends.len() {
+                let end = ends.get(i) as usize;
+                if end < offset {
+                    return Err(GeozeroError::GeometryFormat);
+                }
+                rings.push(read_owned_coords(geometry, offset, end - offset)?);
+                offset = end;
+            }
+            Ok(rings)
+        }
+        _ if len == 0 => Ok(Vec::new()),
+        _ => Ok(vec![read_owned_coords(geometry, 0, len)?]),
+    }
+}
+
+fn read_owned_parts(geometry: &Geometry, allowed: &[GeometryType]) -> Result<Vec<OwnedGeometry>> {
+    let parts = geometry.parts().ok_or(GeozeroError::GeometryFormat)?;
+    let mut geometries = Vec::with_capacity(parts.len());
+    for i in 0..parts.len() {
+        let part = parts.get(i);
+        let geometry_type = part.type_();
+        if !allowed.is_empty() && !allowed.contains(&geometry_type) {
+            return Err(GeozeroError::Geometry(format!(
+                "Unexpected geometry type in {:?}: {:?}",
+                geometry.type_(),
+                geometry_type
+            )));
+        }
+        geometries.push(read_owned_geometry_n(&part, geometry_type)?);
+    }
+    Ok(geometries)
+}
+
+fn read_owned_polygons(geometry: &Geometry) -> Result<Vec<Vec<Vec<Coordinate>>>> {
+    let parts = geometry.parts().ok_or(GeozeroError::GeometryFormat)?;
+    let mut polygons = Vec::with_capacity(parts.len());
+    for i in 0..parts.len() {
+        polygons.push(read_owned_rings(&parts.get(i))?);
+    }
+    Ok(polygons)
+}
+
+/// Read geometry into owned geometry model
+pub fn read_owned_geometry(
+    geometry: &Geometry,
+    geometry_type: GeometryType,
+) -> Result<OwnedGeometry> {
+    let geometry_type = if geometry_type == GeometryType::Unknown {
+        // per feature geometry type
+        geometry.type_()
+    } else {
+        geometry_type
+    };
+    read_owned_geometry_n(geometry, geometry_type)
+}
+
+fn read_owned_geometry_n(
+    geometry: &Geometry,
+    geometry_type: GeometryType,
+) -> Result<OwnedGeometry> {
+    const CURVES: &[GeometryType] = &[
+        GeometryType::LineString,
+        GeometryType::CircularString,
+        GeometryType::CompoundCurve,
+    ];
+    let geom = match geometry_type {
+        GeometryType::Point => {
+            let coord = read_owned_coords(geometry, 0, 1)?;
+            OwnedGeometry::Point(coord[0])
+        }
+        GeometryType::LineString => OwnedGeometry::LineString(read_owned_line(geometry)?),
+        GeometryType::Polygon => OwnedGeometry::Polygon(read_owned_rings(geometry)?),
+        GeometryType::MultiPoint => OwnedGeometry::MultiPoint(read_owned_line(geometry)?),
+        GeometryType::MultiLineString => {
+            OwnedGeometry::MultiLineString(read_owned_rings(geometry)?)
+        }
+        GeometryType::MultiPolygon => OwnedGeometry::MultiPolygon(read_owned_polygons(geometry)?),
+        GeometryType::GeometryCollection => {
+            OwnedGeometry::GeometryCollection(read_owned_parts(geometry, &[])?)
+        }
+        GeometryType::CircularString => OwnedGeometry::CircularString(read_owned_line(geometry)?),
+        GeometryType::CompoundCurve => OwnedGeometry::CompoundCurve(read_owned_parts(
+            geometry,
+            &[GeometryType::LineString, GeometryType::CircularString],
+        )?),
+        GeometryType::CurvePolygon => {
+            OwnedGeometry::CurvePolygon(read_owned_parts(geometry, CURVES)?)
+        }
+        GeometryType::MultiCurve => OwnedGeometry::MultiCurve(read_owned_parts(geometry, CURVES)?),
+        GeometryType::MultiSurface => OwnedGeometry::MultiSurface(read_owned_parts(
+            geometry,
+            &[GeometryType::Polygon, GeometryType::CurvePolygon],
+        )?),
+        GeometryType::PolyhedralSurface => {
+            OwnedGeometry::PolyhedralSurface(read_owned_polygons(geometry)?)
+        }
+        GeometryType::TIN => OwnedGeometry::TIN(read_owned_rings(geometry)?),
+        GeometryType::Triangle => OwnedGeometry::Triangle(read_owned_line(geometry)?),
+        _ => {
+            return Err(GeozeroError::Geometry(format!(
+                "Unknown geometry type {:?}",
+                geometry_type
+            )))
+        }
+    };
+    Ok(geom)
+}
+
+fn expand_bbox(bbox: &mut NodeItem, geometry: &Geometry) {
+    if let Some(xy) = geometry.xy() {
+        for i in (0..xy.len() / 2 * 2).step_by(2) {
+            bbox.expand_xy(xy.get(i), xy.get(i + 1));
+        }
+    }
+    if let Some(parts) = geometry.parts() {
+        for i in 0..parts.len() {
+            expand_bbox(bbox, &parts.get(i));
+        }
+    }
+}
+
 impl Geometry<'_> {
     pub fn process<P: GeomProcessor>(
         &self,
@@ -408,4 +569,14 @@ impl Geometry<'_> {
     ) -> Result<()> {
         read_geometry(processor, self, geometry_type)
     }
+    /// Bounding box of all coordinates
+    pub fn bbox(&self) -> NodeItem {
+        let mut bbox = NodeItem::create(0);
+        expand_bbox(&mut bbox, self);
+        bbox
+    }
+    /// Copy into owned geometry
+    pub fn to_owned_geometry(&self, geometry_type: GeometryType) -> Result<OwnedGeometry> {
+        read_owned_geometry(self, geometry_type)
+    }
 }
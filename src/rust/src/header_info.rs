@@ -0,0 +1,232 @@
+use crate::header_generated::flat_geobuf::*;
+use geozero::CoordDimensions;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+
+/// Owned dataset metadata from the file header
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct HeaderInfo {
+    pub name: Option<String>,
+    pub envelope: Option<Envelope>,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_geometry_type"))]
+    pub geometry_type: GeometryType,
+    pub dimensions: Dimensions,
+    pub columns: Vec<ColumnInfo>,
+    pub features_count: u64,
+    /// Index node size, 0 for datasets without index
+    pub index_node_size: u16,
+    pub crs: Option<CrsInfo>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub metadata: Option<String>,
+}
+
+/// 2D dataset extent
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Envelope {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+/// Coordinate dimensions in addition to x and y
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Dimensions {
+    pub z: bool,
+    pub m: bool,
+    pub t: bool,
+    pub tm: bool,
+}
+
+/// Attribute column
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ColumnInfo {
+    pub name: String,
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "type", serialize_with = "serialize_column_type")
+    )]
+    pub type_: ColumnType,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Width, -1 if unknown
+    pub width: i32,
+    /// Precision, -1 if unknown
+    pub precision: i32,
+    /// Scale, -1 if unknown
+    pub scale: i32,
+    pub nullable: bool,
+    pub unique: bool,
+    pub primary_key: bool,
+    pub metadata: Option<String>,
+}
+
+/// Coordinate reference system
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct CrsInfo {
+    /// Organization, e.g. "EPSG". `None` if not set, which means EPSG by specification.
+    pub org: Option<String>,
+    pub code: i32,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub wkt: Option<String>,
+    /// Non-numeric code
+    pub code_string: Option<String>,
+}
+
+impl From<Header<'_>> for HeaderInfo {
+    fn from(header: Header) -> Self {
+        let envelope = header
+            .envelope()
+            .filter(|envelope| envelope.len() >= 4)
+            .map(|envelope| Envelope {
+                min_x: envelope.get(0),
+                min_y: envelope.get(1),
+                max_x: envelope.get(2),
+                max_y: envelope.get(3),
+            });
+        let columns = header
+            .columns()
+            .map(|columns| {
+                (0..columns.len())
+                    .map(|i| ColumnInfo::from(columns.get(i)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        HeaderInfo {
+            name: header.name().map(String::from),
+            envelope,
+            geometry_type: header.geometry_type(),
+            dimensions: Dimensions {
+                z: header.hasZ(),
+                m: header.hasM(),
+                t: header.hasT(),
+                tm: header.hasTM(),
+            },
+            columns,
+            features_count: header.features_count(),
+            index_node_size: header.index_node_size(),
+            crs: header.crs().map(CrsInfo::from),
+            title: header.title().map(String::from),
+            description: header.description().map(String::from),
+            metadata: header.metadata().map(String::from),
+        }
+    }
+}
+
+impl From<Column<'_>> for ColumnInfo {
+    fn from(column: Column) -> Self {
+        ColumnInfo {
+            name: column.name().to_string(),
+            type_: column.type_(),
+            title: column.title().map(String::from),
+            description: column.description().map(String::from),
+            width: column.width(),
+            precision: column.precision(),
+            scale: column.scale(),
+            nullable: column.nullable(),
+            unique: column.unique(),
+            primary_key: column.primary_key(),
+            metadata: column.metadata().map(String::from),
+        }
+    }
+}
+
+impl From<Crs<'_>> for CrsInfo {
+    fn from(crs: Crs) -> Self {
+        CrsInfo {
+            org: crs.org().map(String::from),
+            code: crs.code(),
+            name: crs.name().map(String::from),
+            description: crs.description().map(String::from),
+            wkt: crs.wkt().map(String::from),
+            code_string: crs.code_string().map(String::from),
+        }
+    }
+}
+
+impl HeaderInfo {
+    /// Column with name `name`
+    pub fn column(&self, name: &str) -> Option<&ColumnInfo> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+}
+
+impl Dimensions {
+    /// Dimensions for geometry processing, e.g. WKB output
+    pub fn coord_dimensions(&self) -> CoordDimensions {
+        CoordDimensions {
+            z: self.z,
+            m: self.m,
+            t: self.t,
+            tm: self.tm,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn serialize_geometry_type<S: Serializer>(
+    geometry_type: &GeometryType,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(geometry_type.variant_name().unwrap_or("Unknown"))
+}
+
+#[cfg(feature = "serde")]
+fn serialize_column_type<S: Serializer>(
+    column_type: &ColumnType,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(column_type.variant_name().unwrap_or("Unknown"))
+}
+
+#[test]
+fn countries_header_info() -> geozero::error::Result<()> {
+    use crate::FgbReader;
+    use std::fs::File;
+    use std::io::BufReader;
+
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let fgb = FgbReader::open(&mut filein)?;
+    let info = HeaderInfo::from(fgb.header());
+    assert_eq!(info.name.as_deref(), Some("countries"));
+    assert_eq!(
+        info.envelope,
+        Some(Envelope {
+            min_x: -180.0,
+            min_y: -85.609038,
+            max_x: 180.0,
+            max_y: 83.64513,
+        })
+    );
+    assert_eq!(info.geometry_type, GeometryType::MultiPolygon);
+    assert_eq!(info.dimensions, Dimensions::default());
+    assert_eq!(info.columns.len(), 2);
+    assert_eq!(info.columns[0].name, "id");
+    assert_eq!(info.column("name").unwrap().type_, ColumnType::String);
+    assert_eq!((info.features_count, info.index_node_size), (179, 16));
+    assert_eq!(info.crs.as_ref().unwrap().code, 4326);
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn countries_header_info_json() -> geozero::error::Result<()> {
+    use crate::FgbReader;
+    use std::fs::File;
+    use std::io::BufReader;
+
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let fgb = FgbReader::open(&mut filein)?;
+    let json = serde_json::to_value(&HeaderInfo::from(fgb.header())).unwrap();
+    assert_eq!(json["geometry_type"], "MultiPolygon");
+    assert_eq!(json["columns"][0]["type"], "String");
+    assert_eq!(json["crs"]["code"], 4326);
+    Ok(())
+}
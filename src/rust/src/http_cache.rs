@@ -0,0 +1,207 @@
+//! Persistent on-disk block cache for HTTP range requests.
+
+use bytes::Bytes;
+use geozero::error::Result;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Block cache shared between HTTP readers.
+///
+/// Remote files are cached in blocks of `block_size` bytes, keyed by URL and ETag.
+/// When the cache directory exceeds `max_size` bytes, the least recently used
+/// blocks are evicted. Files without ETag are never cached.
+///
+/// The last ETag received for a URL is stored in the cache directory as well, so readers
+/// of later sessions are served from the cache without requests. Cached blocks are used
+/// until a request for a missing block returns a different ETag.
+#[derive(Clone)]
+pub struct HttpCache {
+    inner: Arc<Mutex<CacheInner>>,
+}
+
+struct CacheInner {
+    dir: PathBuf,
+    max_size: u64,
+    block_size: usize,
+    /// Cached blocks in LRU order (least recently used first)
+    entries: VecDeque<(String, u64)>,
+    /// Total size of cached blocks
+    size: u64,
+}
+
+impl HttpCache {
+    pub const DEFAULT_BLOCK_SIZE: usize = 65536;
+
+    /// Open cache directory, creating it if needed
+    pub fn open<P: AsRef<Path>>(dir: P, max_size: u64) -> Result<Self> {
+        HttpCache::with_block_size(dir, max_size, HttpCache::DEFAULT_BLOCK_SIZE)
+    }
+
+    pub fn with_block_size<P: AsRef<Path>>(
+        dir: P,
+        max_size: u64,
+        block_size: usize,
+    ) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        // Restore LRU order of previous sessions from file modification times
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let meta = entry.metadata()?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !meta.is_file() || !name.ends_with(".blk") {
+                continue;
+            }
+            files.push((meta.modified()?, name, meta.len()));
+        }
+        files.sort();
+        let mut inner = CacheInner {
+            dir,
+            max_size,
+            block_size: block_size.max(1),
+            entries: VecDeque::with_capacity(files.len()),
+            size: 0,
+        };
+        for (_, name, len) in files {
+            inner.size += len;
+            inner.entries.push_back((name, len));
+        }
+        inner.evict();
+        Ok(HttpCache {
+            inner: Arc::new(Mutex::new(inner)),
+        })
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.inner.lock().unwrap().block_size
+    }
+
+    /// Total size of cached blocks in bytes
+    pub fn size(&self) -> u64 {
+        self.inner.lock().unwrap().size
+    }
+
+    /// Read cached block
+    pub fn get(&self, url: &str, etag: &str, block: usize) -> Option<Bytes> {
+        let mut inner = self.inner.lock().unwrap();
+        let name = block_name(url, etag, block);
+        let pos = inner.entries.iter().position(|(n, _)| n == &name)?;
+        match fs::read(inner.dir.join(&name)) {
+            Ok(data) => {
+                let entry = inner.entries.remove(pos).unwrap();
+                inner.entries.push_back(entry);
+                Some(Bytes::from(data))
+            }
+            Err(_) => {
+                // Removed behind our back
+                let (_, len) = inner.entries.remove(pos).unwrap();
+                inner.size -= len;
+                None
+            }
+        }
+    }
+
+    /// Last ETag stored for `url`
+    pub fn etag(&self, url: &str) -> Option<String> {
+        let inner = self.inner.lock().unwrap();
+        fs::read_to_string(inner.dir.join(etag_name(url))).ok()
+    }
+
+    /// Store ETag of `url`, or remove it if the file has no ETag anymore
+    pub fn set_etag(&self, url: &str, etag: Option<&str>) -> Result<()> {
+        let inner = self.inner.lock().unwrap();
+        let path = inner.dir.join(etag_name(url));
+        match etag {
+            Some(etag) => fs::write(path, etag)?,
+            None if path.exists() => fs::remove_file(path)?,
+            None => {}
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, url: &str, etag: &str, block: usize) -> bool {
+        let name = block_name(url, etag, block);
+        let inner = self.inner.lock().unwrap();
+        inner.entries.iter().any(|(n, _)| n == &name)
+    }
+
+    /// Store block and evict least recently used blocks exceeding the size limit
+    pub fn put(&self, url: &str, etag: &str, block: usize, data: &[u8]) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        let name = block_name(url, etag, block);
+        if let Some(pos) = inner.entries.iter().position(|(n, _)| n == &name) {
+            let (_, len) = inner.entries.remove(pos).unwrap();
+            inner.size -= len;
+        }
+        fs::write(inner.dir.join(&name), data)?;
+        inner.size += data.len() as u64;
+        inner.entries.push_back((name, data.len() as u64));
+        inner.evict();
+        Ok(())
+    }
+}
+
+impl CacheInner {
+    fn evict(&mut self) {
+        while self.size > self.max_size {
+            if let Some((name, len)) = self.entries.pop_front() {
+                let _ = fs::remove_file(self.dir.join(&name));
+                self.size -= len;
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+/// FNV-1a hash, which is stable across processes
+fn fnv_hash(bytes: impl Iterator<Item = u8>) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// File name of cached block
+fn block_name(url: &str, etag: &str, block: usize) -> String {
+    let hash = fnv_hash(url.bytes().chain(Some(0)).chain(etag.bytes()));
+    format!("{:016x}-{}.blk", hash, block)
+}
+
+/// File name of stored ETag
+fn etag_name(url: &str) -> String {
+    format!("{:016x}.etag", fnv_hash(url.bytes()))
+}
+
+#[test]
+fn cache_lru_eviction() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let cache = HttpCache::with_block_size(dir.path(), 24, 10)?;
+    let url = "https://example.com/countries.fgb";
+    cache.put(url, "\"v1\"", 0, &[0; 10])?;
+    cache.put(url, "\"v1\"", 1, &[1; 10])?;
+    assert_eq!(cache.get(url, "\"v1\"", 0).unwrap()[..], [0; 10]);
+    assert!(cache.get(url, "\"v2\"", 0).is_none());
+    // Block 1 is least recently used
+    cache.put(url, "\"v1\"", 2, &[2; 5])?;
+    assert_eq!(cache.size(), 15);
+    assert!(!cache.contains(url, "\"v1\"", 1));
+    assert!(cache.contains(url, "\"v1\"", 0));
+
+    cache.set_etag(url, Some("\"v1\""))?;
+
+    // Reopened cache keeps blocks and ETags of previous session
+    let cache = HttpCache::with_block_size(dir.path(), 24, 10)?;
+    assert_eq!(cache.size(), 15);
+    assert_eq!(cache.get(url, "\"v1\"", 2).unwrap()[..], [2; 5]);
+    assert_eq!(cache.etag(url), Some("\"v1\"".to_string()));
+    assert_eq!(cache.etag("https://example.com/other.fgb"), None);
+    cache.set_etag(url, None)?;
+    assert_eq!(cache.etag(url), None);
+    Ok(())
+}
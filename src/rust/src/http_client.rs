@@ -1,31 +1,109 @@
+use crate::http_cache::HttpCache;
+use crate::s3::{S3Config, S3Object};
 use bytes::{BufMut, Bytes, BytesMut};
+use chrono::Utc;
 use geozero::error::{GeozeroError, Result};
-use std::cmp::max;
+use std::cmp::{max, min};
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
 use std::str;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tracing::Instrument;
+
+/// Range request backend
+enum Backend {
+    Http,
+    S3 {
+        config: S3Config,
+        object: S3Object,
+    },
+    /// Local file, `None` if the URL is not a valid file URL
+    File(Option<PathBuf>),
+}
 
 struct HttpClient {
     client: reqwest::Client,
+    backend: Backend,
     url: String,
+    /// ETag of remote file, known after first request
+    etag: Option<String>,
 }
 
 impl HttpClient {
+    /// Client for `http(s)://`, `s3://` or `file://` URL.
+    /// S3 configuration is read from the environment.
     fn new(url: &str) -> Self {
+        let backend = if url.starts_with("s3://") {
+            let config = S3Config::from_env();
+            let object = config.object(url);
+            Backend::S3 { config, object }
+        } else if url.starts_with("file://") {
+            let path = reqwest::Url::parse(url)
+                .ok()
+                .and_then(|url| url.to_file_path().ok());
+            Backend::File(path)
+        } else {
+            Backend::Http
+        };
         HttpClient {
             client: reqwest::Client::new(),
+            backend,
             url: url.to_string(),
+            etag: None,
+        }
+    }
+    fn with_s3_config(url: &str, config: S3Config) -> Self {
+        let object = config.object(url);
+        HttpClient {
+            backend: Backend::S3 { config, object },
+            ..HttpClient::new(url)
         }
     }
-    async fn get(&self, begin: usize, length: usize) -> Result<Bytes> {
-        let response = self
-            .client
-            .get(&self.url)
-            .header("Range", format!("bytes={}-{}", begin, begin + length - 1))
+    async fn get(&mut self, begin: usize, length: usize) -> Result<Bytes> {
+        let span = tracing::debug_span!(
+            "range_request",
+            url = %self.url,
+            begin,
+            length,
+            bytes = tracing::field::Empty
+        );
+        let bytes = self.request(begin, length).instrument(span.clone()).await?;
+        span.record("bytes", &bytes.len());
+        Ok(bytes)
+    }
+    async fn request(&mut self, begin: usize, length: usize) -> Result<Bytes> {
+        let range = format!("bytes={}-{}", begin, begin + length - 1);
+        let request = match &self.backend {
+            Backend::Http => self.client.get(&self.url).header("Range", range),
+            Backend::S3 { config, object } => {
+                let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+                let mut request = self.client.get(&object.url);
+                for (name, value) in config.range_headers(object, &amz_date, &range) {
+                    request = request.header(name, value);
+                }
+                request
+            }
+            Backend::File(Some(path)) => return read_file_range(path, begin, length).await,
+            Backend::File(None) => {
+                return Err(GeozeroError::HttpError(format!(
+                    "invalid file URL {}",
+                    self.url
+                )))
+            }
+        };
+        let response = request
             .send()
             .await
             .map_err(|e| GeozeroError::HttpError(e.to_string()))?;
         if !response.status().is_success() {
             return Err(GeozeroError::HttpStatus(response.status().as_u16()));
         }
+        self.etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(|etag| etag.to_string());
         response
             .bytes()
             .await
@@ -33,11 +111,42 @@ impl HttpClient {
     }
 }
 
+/// Read range of local file. Like HTTP servers, ranges are truncated at end of file.
+async fn read_file_range(path: &Path, begin: usize, length: usize) -> Result<Bytes> {
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(SeekFrom::Start(begin as u64)).await?;
+    let mut buf = Vec::with_capacity(length);
+    file.take(length as u64).read_to_end(&mut buf).await?;
+    Ok(Bytes::from(buf))
+}
+
+/// HTTP request statistics
+#[derive(Clone, Default, Debug)]
+pub struct HttpStats {
+    /// Number of HTTP range requests
+    pub requests: usize,
+    /// Number of bytes received via HTTP
+    pub bytes: usize,
+    /// Number of blocks read from the persistent cache
+    pub cache_hits: usize,
+    /// Time spent reading the header
+    pub header_time: Duration,
+    /// Time spent searching the index
+    pub index_time: Duration,
+    /// Time spent reading features
+    pub feature_time: Duration,
+}
+
 pub struct BufferedHttpClient {
     http_client: HttpClient,
     buf: BytesMut,
     /// Lower index of buffer relative to input stream
     head: usize,
+    /// Optional persistent block cache
+    cache: Option<HttpCache>,
+    /// Read-ahead is limited to keep the buffer below this size
+    max_buffer_size: usize,
+    pub(crate) stats: HttpStats,
 }
 
 impl BufferedHttpClient {
@@ -46,8 +155,38 @@ impl BufferedHttpClient {
             http_client: HttpClient::new(url),
             buf: BytesMut::new(),
             head: 0,
+            cache: None,
+            max_buffer_size: usize::MAX,
+            stats: HttpStats::default(),
+        }
+    }
+    /// Client for `s3://bucket/key` URL with explicit S3 configuration
+    pub fn with_s3_config(url: &str, config: S3Config) -> Self {
+        BufferedHttpClient {
+            http_client: HttpClient::with_s3_config(url, config),
+            ..BufferedHttpClient::new(url)
         }
     }
+    pub fn with_cache(url: &str, cache: HttpCache) -> Self {
+        let mut client = BufferedHttpClient::new(url);
+        // ETag of a previous session, replaced by the ETag of the next response
+        client.http_client.etag = cache.etag(url);
+        client.cache = Some(cache);
+        client
+    }
+    pub fn set_max_buffer_size(&mut self, max_buffer_size: usize) {
+        self.max_buffer_size = max_buffer_size;
+    }
+    pub fn stats(&self) -> &HttpStats {
+        &self.stats
+    }
+    pub fn reset_stats(&mut self) {
+        self.stats = HttpStats::default();
+    }
+    /// Check whether a range is buffered, i.e. `get` returns it without a request
+    pub fn is_buffered(&self, begin: usize, length: usize) -> bool {
+        begin >= self.head && begin + length <= self.head + self.buf.len()
+    }
     pub async fn get(&mut self, begin: usize, length: usize, min_req_size: usize) -> Result<&[u8]> {
         let tail = self.head + self.buf.len();
         if begin + length > tail || begin < self.head {
@@ -62,12 +201,83 @@ impl BufferedHttpClient {
 
             // Read additional bytes
             let range_begin = max(begin, tail);
-            let range_length = max(begin + length - range_begin, min_req_size);
-            let bytes = self.http_client.get(range_begin, range_length).await?;
+            let max_read_ahead = self.max_buffer_size.saturating_sub(range_begin - self.head);
+            let range_length = max(
+                begin + length - range_begin,
+                min(min_req_size, max_read_ahead),
+            );
+            let bytes = self.fetch(range_begin, range_length).await?;
             self.buf.put(bytes);
         }
         let lower = begin - self.head;
         let upper = begin + length - self.head;
+        if upper > self.buf.len() {
+            // Range beyond end of file
+            return Err(GeozeroError::GeometryFormat);
+        }
         Ok(&self.buf[lower..upper])
     }
+    /// Read range from block cache, requesting missing blocks via HTTP
+    async fn fetch(&mut self, begin: usize, length: usize) -> Result<Bytes> {
+        let cache = match &self.cache {
+            Some(cache) => cache.clone(),
+            None => return self.request(begin, length).await,
+        };
+        let block_size = cache.block_size();
+        let first = begin / block_size;
+        let last = (begin + length - 1) / block_size;
+        let mut buf = BytesMut::with_capacity((last - first + 1) * block_size);
+        let mut block = first;
+        while block <= last {
+            // Without stored ETag, the first request always goes to the network
+            if let Some(etag) = &self.http_client.etag {
+                if let Some(bytes) = cache.get(&self.http_client.url, etag, block) {
+                    tracing::trace!(block, "block cache hit");
+                    self.stats.cache_hits += 1;
+                    let eof = bytes.len() < block_size;
+                    buf.put(bytes);
+                    if eof {
+                        break;
+                    }
+                    block += 1;
+                    continue;
+                }
+            }
+            // Request consecutive missing blocks at once
+            let mut end = block + 1;
+            if let Some(etag) = &self.http_client.etag {
+                while end <= last && !cache.contains(&self.http_client.url, etag, end) {
+                    end += 1;
+                }
+            }
+            let prev_etag = self.http_client.etag.clone();
+            let bytes = self
+                .request(block * block_size, (end - block) * block_size)
+                .await?;
+            if self.http_client.etag != prev_etag {
+                let _ = cache.set_etag(&self.http_client.url, self.http_client.etag.as_deref());
+            }
+            if let Some(etag) = &self.http_client.etag {
+                for (i, chunk) in bytes.chunks(block_size).enumerate() {
+                    // Caching is best effort
+                    let _ = cache.put(&self.http_client.url, etag, block + i, chunk);
+                }
+            }
+            let eof = bytes.len() < (end - block) * block_size;
+            buf.put(bytes);
+            if eof {
+                break;
+            }
+            block = end;
+        }
+        let lower = begin - first * block_size;
+        let upper = min(lower + length, buf.len());
+        Ok(buf.freeze().slice(min(lower, upper)..upper))
+    }
+    async fn request(&mut self, begin: usize, length: usize) -> Result<Bytes> {
+        let bytes = self.http_client.get(begin, length).await?;
+        self.stats.requests += 1;
+        self.stats.bytes += bytes.len();
+        Ok(bytes)
+    }
 }
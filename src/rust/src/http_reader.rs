@@ -1,11 +1,43 @@
+use crate::buffer_check::{check_header, verify_feature};
+use crate::error::{Error, Result};
 use crate::header_generated::flat_geobuf::*;
-use crate::http_client::BufferedHttpClient;
-use crate::packed_r_tree::{self, PackedRTree};
+use crate::http_cache::HttpCache;
+use crate::http_client::{BufferedHttpClient, HttpStats};
+use crate::packed_r_tree::{self, dataset_index_size, PackedRTree};
 use crate::properties_reader::FgbFeature;
-use crate::{HEADER_MAX_BUFFER_SIZE, MAGIC_BYTES};
+use crate::version::Version;
+use crate::HEADER_MAX_BUFFER_SIZE;
 use byteorder::{ByteOrder, LittleEndian};
-use geozero::error::{GeozeroError, Result};
 use geozero::FeatureProcessor;
+use std::time::Instant;
+
+/// Request sizes of the HTTP reader
+#[derive(Clone, Debug)]
+pub struct HttpStrategy {
+    /// Minimal size of the first request, which should cover the header
+    pub header_prefetch: usize,
+    /// Minimal request size when reading index nodes
+    pub index_chunk_size: usize,
+    /// Minimal request size when reading features
+    pub feature_read_ahead: usize,
+    /// Read-ahead is limited to keep the request buffer below this size
+    pub max_buffer_size: usize,
+    /// Size feature read-ahead from the density of selected features,
+    /// using `feature_read_ahead` as upper limit
+    pub adaptive: bool,
+}
+
+impl Default for HttpStrategy {
+    fn default() -> Self {
+        HttpStrategy {
+            header_prefetch: 512,
+            index_chunk_size: 1_048_576,
+            feature_read_ahead: 1_048_576,
+            max_buffer_size: 10 * 1_048_576,
+            adaptive: false,
+        }
+    }
+}
 
 /// FlatGeobuf dataset HTTP reader
 pub struct HttpFgbReader {
@@ -23,24 +55,57 @@ pub struct HttpFgbReader {
     item_filter: Option<Vec<packed_r_tree::SearchResultItem>>,
     /// Current position in item_filter
     feat_no: usize,
+    /// Index levels kept in memory
+    index: Option<PackedRTree>,
+    strategy: HttpStrategy,
+    /// Number of features read, used for adaptive read-ahead
+    features_read: usize,
+    /// Size of features read, used for adaptive read-ahead
+    features_bytes: usize,
+    /// Verify feature buffers
+    verify: bool,
+    /// Format version of the file
+    version: Version,
 }
 
 impl HttpFgbReader {
     pub async fn open(url: &str) -> Result<HttpFgbReader> {
-        let mut client = BufferedHttpClient::new(&url);
-        let min_req_size = 512;
+        HttpFgbReader::from_client(BufferedHttpClient::new(&url), HttpStrategy::default()).await
+    }
+    /// Open dataset using a persistent block cache for HTTP range requests
+    pub async fn open_with_cache(url: &str, cache: HttpCache) -> Result<HttpFgbReader> {
+        HttpFgbReader::from_client(
+            BufferedHttpClient::with_cache(&url, cache),
+            HttpStrategy::default(),
+        )
+        .await
+    }
+    /// Open dataset with custom request sizes
+    pub async fn from_client(
+        mut client: BufferedHttpClient,
+        strategy: HttpStrategy,
+    ) -> Result<HttpFgbReader> {
+        let start = Instant::now();
+        client.set_max_buffer_size(strategy.max_buffer_size);
+        let min_req_size = strategy.header_prefetch;
         let bytes = client.get(0, 8, min_req_size).await?;
-        if bytes != MAGIC_BYTES {
-            return Err(GeozeroError::GeometryFormat);
-        }
+        let mut magic_buf = [0; 8];
+        magic_buf.copy_from_slice(bytes);
+        let version = Version::from_magic_bytes(&magic_buf)?;
         let bytes = client.get(8, 12, min_req_size).await?;
         let header_size = LittleEndian::read_u32(bytes) as usize;
         if header_size > HEADER_MAX_BUFFER_SIZE || header_size < 8 {
             // minimum size check avoids panic in FlatBuffers header decoding
-            return Err(GeozeroError::GeometryFormat);
+            return Err(Error::HeaderSize {
+                size: header_size,
+                max: HEADER_MAX_BUFFER_SIZE,
+            });
         }
-        let bytes = client.get(12, header_size, min_req_size).await?;
+        // header buffer including size prefix
+        let bytes = client.get(8, 4 + header_size, min_req_size).await?;
         let header_buf = bytes.to_vec();
+        check_header(&header_buf)?;
+        client.stats.header_time += start.elapsed();
 
         Ok(HttpFgbReader {
             client,
@@ -53,23 +118,75 @@ impl HttpFgbReader {
             feature_base: 0,
             item_filter: None,
             feat_no: 0,
+            index: None,
+            strategy,
+            features_read: 0,
+            features_bytes: 0,
+            verify: true,
+            version,
         })
     }
-    pub fn header(&self) -> Header {
+    pub fn header(&self) -> Header<'_> {
         self.fbs.header()
     }
+    /// Format version of the file
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    /// Enable or disable verification of feature buffers (enabled by default).
+    /// The header is always verified, because it is read by `open` before verification
+    /// can be disabled. Its verification cost is negligible compared to reading features.
+    ///
+    /// # Safety
+    ///
+    /// Features are accessed with the unchecked flatbuffers accessors, so disabling
+    /// verification is only sound for trusted data. Corrupt features can cause panics
+    /// or undefined behaviour.
+    pub unsafe fn set_verify(&mut self, verify: bool) {
+        self.verify = verify;
+    }
+    pub fn strategy(&self) -> &HttpStrategy {
+        &self.strategy
+    }
+    pub fn set_strategy(&mut self, strategy: HttpStrategy) {
+        self.client.set_max_buffer_size(strategy.max_buffer_size);
+        self.strategy = strategy;
+    }
     fn header_len(&self) -> usize {
-        12 + self.fbs.header_buf.len()
+        8 + self.fbs.header_buf.len()
+    }
+    /// Load the upper `levels` levels of the R-Tree index into memory, or the full
+    /// index with `usize::MAX`. Subsequent `select_bbox` calls only request the
+    /// remaining levels via HTTP.
+    pub async fn load_index(&mut self, levels: usize) -> Result<()> {
+        let start = Instant::now();
+        let header = self.fbs.header();
+        let count = header.features_count() as usize;
+        let index_node_size = header.index_node_size();
+        let header_len = self.header_len();
+        let index = PackedRTree::from_http_levels(
+            &mut self.client,
+            header_len,
+            count,
+            index_node_size,
+            levels,
+        )
+        .await?;
+        self.index = Some(index);
+        self.client.stats.index_time += start.elapsed();
+        Ok(())
     }
     /// Select all features.  Returns feature count.
     pub async fn select_all(&mut self) -> Result<usize> {
         let header = self.fbs.header();
         let count = header.features_count() as usize;
-        let index_size = PackedRTree::index_size(count, header.index_node_size());
+        let index_size = self.version.index_size(count, header.index_node_size())?;
         // Skip index
         self.feature_base = self.header_len() + index_size;
         self.pos = self.feature_base;
         self.count = count;
+        self.item_filter = None;
+        self.feat_no = 0;
         Ok(count)
     }
     /// Select features within a bounding box. Returns count of selected features.
@@ -81,25 +198,60 @@ impl HttpFgbReader {
         max_y: f64,
     ) -> Result<usize> {
         // Read R-Tree index and build filter for features within bbox
+        let start = Instant::now();
         let header = self.fbs.header();
         let count = header.features_count() as usize;
         let header_len = self.header_len();
-        let list = PackedRTree::http_stream_search(
-            &mut self.client,
-            header_len,
-            count,
-            PackedRTree::DEFAULT_NODE_SIZE,
-            min_x,
-            min_y,
-            max_x,
-            max_y,
-        )
-        .await?;
-        let index_size = PackedRTree::index_size(count, header.index_node_size());
-        self.feature_base = self.header_len() + index_size;
+        if self.index.is_none() {
+            // index nodes are read on demand
+            let index = PackedRTree::from_http_levels(
+                &mut self.client,
+                header_len,
+                count,
+                header.index_node_size(),
+                0,
+            )
+            .await?;
+            self.index = Some(index);
+        }
+        let index = self.index.as_ref().ok_or(Error::IndexNotLoaded)?;
+        let mut list = index
+            .http_search(
+                &mut self.client,
+                header_len,
+                self.strategy.index_chunk_size,
+                min_x,
+                min_y,
+                max_x,
+                max_y,
+            )
+            .await?;
+        let node_size = header.index_node_size();
+        if self.version.has_feature_offsets() {
+            // resolve feature numbers with the offsets table following the index
+            let table_base = header_len + dataset_index_size(count, node_size)?;
+            for item in &mut list {
+                let bytes = self
+                    .client
+                    .get(
+                        table_base + item.offset * 8,
+                        8,
+                        self.strategy.index_chunk_size,
+                    )
+                    .await?;
+                item.offset = LittleEndian::read_u64(bytes) as usize;
+            }
+        }
+        // read features sequentially, which is also required by the adaptive read-ahead
+        list.sort_by_key(|item| item.offset);
+        debug_assert!(list.windows(2).all(|w| w[0].offset < w[1].offset));
+        let index_size = self.version.index_size(count, node_size)?;
+        self.feature_base = header_len + index_size;
         self.pos = self.feature_base;
         self.count = list.len();
         self.item_filter = Some(list);
+        self.feat_no = 0;
+        self.client.stats.index_time += start.elapsed();
         Ok(self.count)
     }
     /// Number of selected features
@@ -108,26 +260,81 @@ impl HttpFgbReader {
     }
     /// Read next feature
     pub async fn next(&mut self) -> Result<Option<&FgbFeature>> {
-        let min_req_size = 1_048_576; // 1MB
         if self.feat_no >= self.count {
             return Ok(None);
         }
+        let mut feature = self.feat_no;
         if let Some(filter) = &self.item_filter {
             let item = &filter[self.feat_no];
+            feature = item.index;
             self.pos = self.feature_base + item.offset;
         }
-        self.feat_no += 1;
-        let bytes = self.client.get(self.pos, 4, min_req_size).await?;
-        self.pos += 4;
+        let start = Instant::now();
+        let offset = self.pos;
+        let min_req_size = self.feature_req_size(offset, 4);
+        let bytes = self.client.get(offset, 4, min_req_size).await?;
         let feature_size = LittleEndian::read_u32(bytes) as usize;
+        let min_req_size = self.feature_req_size(offset, 4 + feature_size);
+        self.feat_no += 1;
+        // feature buffer including size prefix
         let bytes = self
             .client
-            .get(self.pos, feature_size, min_req_size)
+            .get(offset, 4 + feature_size, min_req_size)
             .await?;
         self.fbs.feature_buf = bytes.to_vec(); // Not zero-copy
-        self.pos += feature_size;
+        if self.verify {
+            if let Err(message) = verify_feature(&self.fbs.feature_buf) {
+                return Err(Error::InvalidFeature {
+                    feature,
+                    offset: offset as u64,
+                    message,
+                });
+            }
+        }
+        self.pos = offset + 4 + feature_size;
+        self.features_read += 1;
+        self.features_bytes += 4 + feature_size;
+        self.client.stats.feature_time += start.elapsed();
         Ok(Some(&self.fbs))
     }
+    /// Minimal request size for reading `length` bytes of the next feature at `offset`.
+    /// The read-ahead window is only computed if a range request is required.
+    fn feature_req_size(&self, offset: usize, length: usize) -> usize {
+        if self.client.is_buffered(offset, length) {
+            length
+        } else {
+            self.feature_read_ahead()
+        }
+    }
+    /// Minimal request size for reading the next feature
+    fn feature_read_ahead(&self) -> usize {
+        let max_read_ahead = self.strategy.feature_read_ahead;
+        let filter = match &self.item_filter {
+            Some(filter) if self.strategy.adaptive => filter,
+            _ => return max_read_ahead,
+        };
+        // Cover selected features following within the read-ahead limit.
+        // The filter is ordered by offset, see `select_bbox`.
+        let avg_size = if self.features_read > 0 {
+            self.features_bytes / self.features_read
+        } else {
+            4
+        };
+        let begin = filter[self.feat_no].offset;
+        let following = &filter[self.feat_no + 1..];
+        let len =
+            following.partition_point(|item| item.offset - begin + avg_size <= max_read_ahead);
+        let end = following[..len].last().map_or(begin, |item| item.offset);
+        end - begin + avg_size
+    }
+    /// HTTP request statistics
+    pub fn stats(&self) -> &HttpStats {
+        self.client.stats()
+    }
+    /// Reset HTTP request statistics
+    pub fn reset_stats(&mut self) {
+        self.client.reset_stats()
+    }
     /// Return current feature
     pub fn cur_feature(&self) -> &FgbFeature {
         &self.fbs
@@ -140,6 +347,7 @@ impl HttpFgbReader {
             feature.process(out, cnt)?;
             cnt += 1;
         }
-        out.dataset_end()
+        out.dataset_end()?;
+        Ok(())
     }
 }
@@ -0,0 +1,357 @@
+//! Building a packed Hilbert R-Tree for datasets larger than memory.
+
+use crate::error::{Error, Result};
+use crate::packed_r_tree::{NodeItem, PackedRTree, SpaceFillingCurve};
+use byteorder::{ByteOrder, LittleEndian};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+
+/// Feature with its curve index
+struct RunItem {
+    key: u64,
+    node: NodeItem,
+    feature: Vec<u8>,
+}
+
+/// Sorted run spilled to a temporary file
+struct Run {
+    file: File,
+    len: usize,
+}
+
+/// Index builder with bounded memory usage.
+///
+/// Features are collected together with their bounding boxes and sorted by the Hilbert value
+/// of the box center (or another `SpaceFillingCurve`). Sorted runs exceeding the memory budget are spilled to temporary files
+/// and merged when finishing. Index levels are generated bottom-up from temporary files,
+/// so the index is never held in memory as a whole.
+///
+/// The resulting index and feature order are identical to `sort_by_curve` and `PackedRTree::build`,
+/// given that `extent` is the extent of all features.
+pub struct ExternalIndexBuilder {
+    extent: NodeItem,
+    node_size: u16,
+    curve: SpaceFillingCurve,
+    memory_budget: usize,
+    temp_dir: PathBuf,
+    /// Current run
+    run: Vec<RunItem>,
+    /// Memory used by current run
+    run_size: usize,
+    runs: Vec<Run>,
+    num_items: usize,
+}
+
+impl ExternalIndexBuilder {
+    pub const DEFAULT_MEMORY_BUDGET: usize = 256 * 1024 * 1024;
+
+    /// Create builder for features within `extent`
+    pub fn new(extent: NodeItem, node_size: u16) -> Self {
+        ExternalIndexBuilder {
+            extent,
+            node_size: node_size.max(2),
+            curve: SpaceFillingCurve::default(),
+            memory_budget: ExternalIndexBuilder::DEFAULT_MEMORY_BUDGET,
+            temp_dir: std::env::temp_dir(),
+            run: Vec::new(),
+            run_size: 0,
+            runs: Vec::new(),
+            num_items: 0,
+        }
+    }
+
+    /// Curve for ordering features. Must be set before adding features.
+    pub fn set_curve(&mut self, curve: SpaceFillingCurve) {
+        self.curve = curve;
+    }
+
+    /// Approximate memory used for sorting and merging in bytes
+    pub fn set_memory_budget(&mut self, memory_budget: usize) {
+        self.memory_budget = memory_budget;
+    }
+
+    /// Directory for temporary files
+    pub fn set_temp_dir<P: AsRef<Path>>(&mut self, temp_dir: P) {
+        self.temp_dir = temp_dir.as_ref().to_path_buf();
+    }
+
+    /// Number of features added
+    pub fn num_items(&self) -> usize {
+        self.num_items
+    }
+
+    /// Number of runs spilled to disk
+    pub fn num_runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Add feature with bounding box. `feature` contains the size prefixed feature buffer.
+    pub fn push(&mut self, bbox: &NodeItem, feature: &[u8]) -> Result<()> {
+        let mut node = bbox.clone();
+        node.set_offset(0);
+        self.run.push(RunItem {
+            key: self.curve.index(&node, &self.extent),
+            node,
+            feature: feature.to_vec(),
+        });
+        self.run_size += size_of::<RunItem>() + feature.len();
+        self.num_items += 1;
+        if self.run_size >= self.memory_budget {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Sort current run in curve order (descending, stable like `sort_by_curve`)
+    fn sort_run(&mut self) {
+        self.run.sort_by(|a, b| b.key.cmp(&a.key));
+    }
+
+    /// Write current run to a temporary file
+    fn spill(&mut self) -> Result<()> {
+        if self.run.is_empty() {
+            return Ok(());
+        }
+        self.sort_run();
+        let mut out = BufWriter::new(tempfile::tempfile_in(&self.temp_dir)?);
+        for item in &self.run {
+            write_item(&mut out, item)?;
+        }
+        let file = into_file(out)?;
+        self.runs.push(Run {
+            file,
+            len: self.run.len(),
+        });
+        self.run.clear();
+        self.run_size = 0;
+        Ok(())
+    }
+
+    /// Visit all features in curve order
+    fn for_each_item<F: FnMut(&RunItem) -> Result<()>>(&mut self, mut f: F) -> Result<()> {
+        if self.runs.is_empty() {
+            for item in &self.run {
+                f(item)?;
+            }
+            return Ok(());
+        }
+        let buf_size = (self.memory_budget / (self.runs.len() + 1)).max(4096);
+        let mut readers = Vec::with_capacity(self.runs.len());
+        let mut remaining = Vec::with_capacity(self.runs.len());
+        let mut heap = BinaryHeap::with_capacity(self.runs.len());
+        let mut heads = Vec::with_capacity(self.runs.len());
+        for run in self.runs.iter_mut() {
+            run.file.seek(SeekFrom::Start(0))?;
+        }
+        let run_error = |run: usize, item: usize, e: Error| Error::IndexRun {
+            run,
+            item,
+            message: e.to_string(),
+        };
+        for (i, run) in self.runs.iter().enumerate() {
+            let mut reader = BufReader::with_capacity(buf_size, &run.file);
+            let item = read_item(&mut reader).map_err(|e| run_error(i, 0, e))?;
+            // equal curve indices keep insertion order, i.e. run order
+            heap.push((item.key, Reverse(i)));
+            heads.push(Some(item));
+            readers.push(reader);
+            remaining.push(run.len - 1);
+        }
+        while let Some((_, Reverse(i))) = heap.pop() {
+            let item = heads[i].take().ok_or_else(|| Error::IndexRun {
+                run: i,
+                item: self.runs[i].len - 1 - remaining[i],
+                message: "merged item missing".to_string(),
+            })?;
+            f(&item)?;
+            if remaining[i] > 0 {
+                let read = self.runs[i].len - remaining[i];
+                remaining[i] -= 1;
+                let next = read_item(&mut readers[i]).map_err(|e| run_error(i, read, e))?;
+                heap.push((next.key, Reverse(i)));
+                heads[i] = Some(next);
+            }
+        }
+        Ok(())
+    }
+
+    /// Write index followed by the features in index order
+    pub fn finish(mut self, out: &mut dyn Write) -> Result<()> {
+        if self.num_items == 0 {
+            return Ok(());
+        }
+        if self.runs.is_empty() {
+            self.sort_run();
+        } else {
+            self.spill()?;
+        }
+        let level_bounds = PackedRTree::generate_level_bounds(self.num_items, self.node_size)?;
+
+        // Leaf nodes with feature offsets
+        let mut leaves = BufWriter::new(tempfile::tempfile_in(&self.temp_dir)?);
+        let mut offset = 0;
+        let mut raw = [0; size_of::<NodeItem>()];
+        self.for_each_item(|item| {
+            let mut node = item.node.clone();
+            node.set_offset(offset);
+            node.write_bytes(&mut raw);
+            leaves.write_all(&raw)?;
+            offset += item.feature.len() as u64;
+            Ok(())
+        })?;
+        let mut levels = vec![into_file(leaves)?];
+
+        // Parent levels, bottom-up
+        for i in 0..level_bounds.len() - 1 {
+            let (start, end) = level_bounds[i];
+            let child_level = levels.last_mut().ok_or(Error::IndexLevel(i))?;
+            child_level.seek(SeekFrom::Start(0))?;
+            let mut children = BufReader::new(&*child_level);
+            let mut parents = BufWriter::new(tempfile::tempfile_in(&self.temp_dir)?);
+            let mut pos = start;
+            while pos < end {
+                let mut node = NodeItem::create(pos as u64);
+                for _ in 0..self.node_size {
+                    if pos >= end {
+                        break;
+                    }
+                    children.read_exact(&mut raw)?;
+                    node.expand(&NodeItem::from_bytes(&raw));
+                    pos += 1;
+                }
+                node.write_bytes(&mut raw);
+                parents.write_all(&raw)?;
+            }
+            drop(children);
+            levels.push(into_file(parents)?);
+        }
+
+        // Index is stored top-down
+        for level in levels.iter_mut().rev() {
+            level.seek(SeekFrom::Start(0))?;
+            io::copy(level, out)?;
+        }
+
+        self.for_each_item(|item| {
+            out.write_all(&item.feature)?;
+            Ok(())
+        })
+    }
+}
+
+fn into_file(writer: BufWriter<File>) -> Result<File> {
+    Ok(writer
+        .into_inner()
+        .map_err(|e| io::Error::from(e.error().kind()))?)
+}
+
+fn write_item<W: Write>(out: &mut W, item: &RunItem) -> Result<()> {
+    let mut buf = [0; 8 + size_of::<NodeItem>() + 4];
+    LittleEndian::write_u64(&mut buf[0..8], item.key);
+    item.node.write_bytes(&mut buf[8..48]);
+    LittleEndian::write_u32(&mut buf[48..52], item.feature.len() as u32);
+    out.write_all(&buf)?;
+    out.write_all(&item.feature)?;
+    Ok(())
+}
+
+fn read_item<R: Read>(data: &mut R) -> Result<RunItem> {
+    let mut buf = [0; 8 + size_of::<NodeItem>() + 4];
+    data.read_exact(&mut buf)?;
+    let mut feature = vec![0; LittleEndian::read_u32(&buf[48..52]) as usize];
+    data.read_exact(&mut feature)?;
+    Ok(RunItem {
+        key: LittleEndian::read_u64(&buf[0..8]),
+        node: NodeItem::from_bytes(&buf[8..48]),
+        feature,
+    })
+}
+
+#[cfg(test)]
+use crate::packed_r_tree::{calc_extent, sort_by_curve};
+
+#[cfg(test)]
+fn build_in_memory(
+    nodes: &[NodeItem],
+    features: &[Vec<u8>],
+    curve: SpaceFillingCurve,
+) -> Result<Vec<u8>> {
+    // offset is used as feature id before sorting
+    let mut sorted: Vec<NodeItem> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| {
+            let mut node = node.clone();
+            node.set_offset(i as u64);
+            node
+        })
+        .collect();
+    sort_by_curve(&mut sorted, curve);
+    let mut feature_data = Vec::new();
+    for node in &mut sorted {
+        let id = node.offset() as usize;
+        node.set_offset(feature_data.len() as u64);
+        feature_data.extend_from_slice(&features[id]);
+    }
+    let extent = calc_extent(&sorted);
+    let tree = PackedRTree::build(&sorted, &extent, 4)?;
+    let mut out = Vec::new();
+    tree.stream_write(&mut out)?;
+    out.extend(feature_data);
+    Ok(out)
+}
+
+#[test]
+fn external_index_matches_in_memory() -> Result<()> {
+    let nodes: Vec<NodeItem> = (0..1000)
+        .map(|i| {
+            let x = ((i * 7919) % 1000) as f64;
+            let y = ((i * 104729) % 997) as f64;
+            NodeItem::new(x, y, x + (i % 5) as f64, y + 1.0)
+        })
+        .collect();
+    let features: Vec<Vec<u8>> = (0..1000).map(|i| vec![i as u8; 1 + i % 13]).collect();
+    for curve in &[SpaceFillingCurve::Hilbert16, SpaceFillingCurve::ZOrder] {
+        let expected = build_in_memory(&nodes, &features, *curve)?;
+        for memory_budget in &[usize::MAX, 2000, 500] {
+            let mut builder = ExternalIndexBuilder::new(calc_extent(&nodes), 4);
+            builder.set_curve(*curve);
+            builder.set_memory_budget(*memory_budget);
+            for (node, feature) in nodes.iter().zip(&features) {
+                builder.push(node, feature)?;
+            }
+            if *memory_budget < 1000 {
+                assert!(builder.num_runs() > 10);
+            }
+            let mut out = Vec::new();
+            builder.finish(&mut out)?;
+            assert_eq!(out, expected);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn external_index_corrupt_run() -> Result<()> {
+    let nodes: Vec<NodeItem> = (0..100)
+        .map(|i| NodeItem::new(i as f64, 0.0, i as f64 + 1.0, 1.0))
+        .collect();
+    let mut builder = ExternalIndexBuilder::new(calc_extent(&nodes), 4);
+    builder.set_memory_budget(1000);
+    for node in &nodes {
+        builder.push(node, &[1, 2, 3])?;
+    }
+    assert!(builder.num_runs() > 1);
+    // truncate second run within its third item
+    let item_size = 8 + size_of::<NodeItem>() + 4 + 3;
+    builder.runs[1].file.set_len(2 * item_size as u64 + 10)?;
+    match builder.finish(&mut Vec::new()) {
+        Err(Error::IndexRun { run, item, .. }) => assert_eq!((run, item), (1, 2)),
+        result => panic!("run error expected, got {:?}", result),
+    }
+    Ok(())
+}
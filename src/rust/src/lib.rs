@@ -8,7 +8,7 @@
 //!
 //! ```ini
 //! [dependencies]
-//! flatgeobuf = "0.3"
+//! flatgeobuf = "0.4"
 //! ```
 //!
 //! ## Reading a FlatGeobuf file
@@ -30,6 +30,38 @@
 //! # }
 //! ```
 //!
+//! With `select_bbox_lazy`, features are read while the index is searched, so reading starts
+//! immediately, even for selections covering most of a large dataset.
+//!
+//! Header and feature buffers are verified with the flatbuffers verifier before they are
+//! accessed, so corrupt files result in errors instead of panics. Feature verification can be
+//! disabled for trusted data with the unsafe `FgbReader::set_verify(false)`. The header is
+//! verified once when opening a file and this can't be disabled.
+//!
+//! Files of format version 2 are read as well. The version of an opened file is returned by
+//! `FgbReader::version()`, other major versions are rejected with `Error::UnsupportedVersion`.
+//!
+//! ## Dataset metadata
+//!
+//! `HeaderInfo` is an owned copy of the header with typed columns, envelope and CRS.
+//! With the `serde` feature, it implements `serde::Serialize`, e.g. for returning layer metadata
+//! from a web service.
+//!
+//! ```rust
+//! # use flatgeobuf::*;
+//! # use std::fs::File;
+//! # use std::io::BufReader;
+//! # fn read_fbg() -> geozero::error::Result<()> {
+//! # let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+//! let fgb = FgbReader::open(&mut filein)?;
+//! let info = HeaderInfo::from(fgb.header());
+//! for column in &info.columns {
+//!     println!("{}: {:?}", column.name, column.type_);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! ## Zero-copy geometry reader
 //!
 //! Geometries can be accessed by implementing the `GeomProcessor` trait.
@@ -92,6 +124,131 @@
 //! # }
 //! ```
 //!
+//! ## Parallel feature decoding
+//!
+//! With the `rayon` feature, selected features are decoded on the rayon thread pool
+//! with `FgbReader::par_map_features` (results in feature order) or
+//! `FgbReader::par_process_features` (one processor per thread).
+//! Indexes are built in parallel with `par_hilbert_sort` and `PackedRTree::par_build`.
+//!
+//! ## Spatial join
+//!
+//! `FgbReader::join` pairs the features of two datasets with intersecting bounding boxes by
+//! traversing both indexes at once. `FgbReader::join_refined` additionally checks each candidate
+//! pair with an exact predicate like `point_in_polygon`:
+//!
+//! ```rust
+//! use flatgeobuf::*;
+//! # use std::fs::File;
+//! # use std::io::BufReader;
+//!
+//! # fn join() -> geozero::error::Result<()> {
+//! let mut parcels_file = BufReader::new(File::open("parcels.fgb")?);
+//! let mut parcels = FgbReader::open(&mut parcels_file)?;
+//! let mut addresses_file = BufReader::new(File::open("addresses.fgb")?);
+//! let mut addresses = FgbReader::open(&mut addresses_file)?;
+//! for (parcel, address) in parcels.join_refined(&mut addresses, point_in_polygon)? {
+//!     println!("address {} is in parcel {}", address.index, parcel.index);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Building indexes
+//!
+//! Index items are ordered with `hilbert_sort` before calling `PackedRTree::build`.
+//! `sort_by_curve` orders items along a 32 bit Hilbert curve or a Z-order curve instead,
+//! which improves query locality of very dense datasets. `ExternalIndexBuilder` builds
+//! indexes of datasets larger than memory.
+//! `PackedRTree::stats` reports structure and quality metrics of an index, e.g. of an existing
+//! file read with `FgbReader::read_index`.
+//!
+//! ## Owned geometries and WKB
+//!
+//! `Geometry::to_owned_geometry` copies a geometry into an `OwnedGeometry`, which can be kept
+//! beyond the reader lifetime and encoded back with `OwnedGeometry::to_fbs`.
+//! Geometries can be encoded as ISO WKB or PostGIS EWKB with `FgbFeature::geometry_wkb` and
+//! `FgbFeature::geometry_ewkb`, which embeds the SRID of the header CRS.
+//!
+//! ## geo-types conversion
+//!
+//! With the `geo-types` feature, feature geometries can be converted with `FgbFeature::geometry_geo`.
+//!
+//! ## File verification
+//!
+//! `verify` checks the header, the index and all features of a file and reports every problem found:
+//!
+//! ```rust
+//! # use std::fs::File;
+//! # use std::io::BufReader;
+//! # fn verify_file() -> geozero::error::Result<()> {
+//! let mut filein = BufReader::new(File::open("countries.fgb")?);
+//! let report = flatgeobuf::verify(&mut filein)?;
+//! for problem in &report.problems {
+//!     println!("{:?}", problem);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Errors
+//!
+//! Readers and index functions return `flatgeobuf::Error`, which includes context like feature
+//! numbers, byte offsets and expected sizes. It converts into `GeozeroError`, so `?` works in
+//! functions returning `geozero::error::Result`. Converted errors keep the original error:
+//!
+//! ```rust
+//! # use std::fs::File;
+//! # use std::io::BufReader;
+//! # use flatgeobuf::*;
+//! # fn read_fbg() -> geozero::error::Result<()> {
+//! # let mut filein = BufReader::new(File::open("countries.fgb")?);
+//! # let mut fgb = FgbReader::open(&mut filein)?;
+//! # fgb.select_all()?;
+//! fn next_name(fgb: &mut FgbReader) -> geozero::error::Result<Option<String>> {
+//!     match fgb.next()? {
+//!         Some(feature) => Ok(feature.properties()?.remove("name")),
+//!         None => Ok(None),
+//!     }
+//! }
+//! if let Err(e) = next_name(&mut fgb) {
+//!     if let Some(Error::TruncatedFeature { feature, .. }) = Error::from_geozero(&e) {
+//!         println!("file ends within feature {}", feature);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ### Upgrading from 0.3
+//!
+//! Version 0.4 changes the error type of the public API:
+//! * `FgbReader`, `AsyncFgbReader`, `HttpFgbReader` and `PackedRTree` methods return
+//!   `Result<_, flatgeobuf::Error>` instead of `geozero::error::Result`. Callers using `?` in
+//!   functions returning `geozero::error::Result` are unaffected, explicit matches on
+//!   `GeozeroError` need `Error::from_geozero` or a match on `flatgeobuf::Error`.
+//! * `PackedRTree::index_size` returns `Result<usize>`, since invalid parameters are reported
+//!   as errors instead of panics.
+//!
+//! ## Async reading
+//!
+//! Files can be read without blocking the executor from any tokio `AsyncRead + AsyncSeek` stream.
+//!
+//! ```rust
+//! use flatgeobuf::*;
+//!
+//! # async fn read_fbg() -> geozero::error::Result<()> {
+//! let file = tokio::fs::File::open("countries.fgb").await?;
+//! let mut fgb = AsyncFgbReader::open(file).await?;
+//! fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?;
+//! while let Some(feature) = fgb.next().await? {
+//!     let props = feature.properties()?;
+//!     println!("{}", props["name"]);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! ## Reading FlatGeobuf via HTTP
 //!
 //! ```rust
@@ -108,30 +265,99 @@
 //! # }
 //! ```
 //!
+//! Besides HTTP, datasets can be read from S3-compatible object stores (`s3://bucket/key`,
+//! configured with the `AWS_*` environment variables or `S3Config`) and from local files (`file://`).
+//!
+//! Repeated requests can be served from a persistent on-disk cache:
+//!
+//! ```rust
+//! use flatgeobuf::*;
+//!
+//! # async fn read_fbg() -> geozero::error::Result<()> {
+//! let cache = HttpCache::open("/tmp/fgb-cache", 256 * 1024 * 1024)?;
+//! let mut fgb = HttpFgbReader::open_with_cache("https://pkg.sourcepole.ch/countries.fgb", cache).await?;
+//! fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Request sizes are configurable with `HttpStrategy`:
+//!
+//! ```rust
+//! use flatgeobuf::*;
+//!
+//! # async fn read_fbg() -> geozero::error::Result<()> {
+//! let url = "https://pkg.sourcepole.ch/countries.fgb";
+//! let strategy = HttpStrategy {
+//!     header_prefetch: 4096,
+//!     adaptive: true,
+//!     ..Default::default()
+//! };
+//! let mut fgb = HttpFgbReader::from_client(BufferedHttpClient::new(url), strategy).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
 
+mod async_reader;
+mod buffer_check;
 #[cfg(not(target_arch = "wasm32"))]
 mod driver;
-#[allow(dead_code, unused_imports, non_snake_case)]
+mod error;
+#[allow(
+    dead_code,
+    unused_imports,
+    non_snake_case,
+    mismatched_lifetime_syntaxes,
+    clippy::all
+)]
 mod feature_generated;
 mod file_reader;
+#[cfg(feature = "geo-types")]
+mod geo_types_conv;
 mod geometry_reader;
-#[allow(dead_code, unused_imports, non_snake_case)]
+#[allow(
+    dead_code,
+    unused_imports,
+    non_snake_case,
+    mismatched_lifetime_syntaxes,
+    clippy::all
+)]
 mod header_generated;
+mod header_info;
+mod http_cache;
 mod http_client;
 mod http_reader;
+mod index_builder;
+mod owned_geometry;
 mod packed_r_tree;
 mod properties_reader;
+mod s3;
+mod spatial_join;
+mod verify;
+mod version;
+mod wkb_writer;
 
+pub use async_reader::*;
 #[cfg(not(target_arch = "wasm32"))]
 pub use driver::*;
+pub use error::Error;
 pub use feature_generated::flat_geobuf::*;
 pub use file_reader::*;
 pub use geometry_reader::*;
 pub use header_generated::flat_geobuf::*;
+pub use header_info::*;
+pub use http_cache::*;
 pub use http_client::*;
 pub use http_reader::*;
+pub use index_builder::ExternalIndexBuilder;
+pub use owned_geometry::*;
 pub use packed_r_tree::*;
 pub use properties_reader::*;
+pub use s3::S3Config;
+pub use spatial_join::*;
+pub use verify::*;
+pub use version::Version;
 
 pub const VERSION: u8 = 3;
 pub const MAGIC_BYTES: [u8; 8] = [b'f', b'g', b'b', VERSION, b'f', b'g', b'b', 0];
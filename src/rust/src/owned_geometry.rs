@@ -0,0 +1,298 @@
+use crate::feature_generated::flat_geobuf::*;
+use crate::header_generated::flat_geobuf::*;
+use flatbuffers::{FlatBufferBuilder, WIPOffset};
+#[cfg(test)]
+use geozero::error::Result;
+
+/// Coordinate with optional dimensions
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Coordinate {
+    pub x: f64,
+    pub y: f64,
+    pub z: Option<f64>,
+    pub m: Option<f64>,
+    pub t: Option<f64>,
+    pub tm: Option<u64>,
+}
+
+impl Coordinate {
+    pub fn xy(x: f64, y: f64) -> Self {
+        Coordinate {
+            x,
+            y,
+            ..Default::default()
+        }
+    }
+}
+
+/// Owned geometry, mirroring `GeometryType`
+#[derive(Clone, Debug, PartialEq)]
+pub enum OwnedGeometry {
+    Point(Coordinate),
+    LineString(Vec<Coordinate>),
+    /// Rings, starting with the exterior ring
+    Polygon(Vec<Vec<Coordinate>>),
+    MultiPoint(Vec<Coordinate>),
+    MultiLineString(Vec<Vec<Coordinate>>),
+    MultiPolygon(Vec<Vec<Vec<Coordinate>>>),
+    GeometryCollection(Vec<OwnedGeometry>),
+    CircularString(Vec<Coordinate>),
+    /// Segments of type LineString or CircularString
+    CompoundCurve(Vec<OwnedGeometry>),
+    /// Rings of type LineString, CircularString or CompoundCurve
+    CurvePolygon(Vec<OwnedGeometry>),
+    /// Curves of type LineString, CircularString or CompoundCurve
+    MultiCurve(Vec<OwnedGeometry>),
+    /// Surfaces of type Polygon or CurvePolygon
+    MultiSurface(Vec<OwnedGeometry>),
+    PolyhedralSurface(Vec<Vec<Vec<Coordinate>>>),
+    /// Triangles, each a closed ring
+    TIN(Vec<Vec<Coordinate>>),
+    Triangle(Vec<Coordinate>),
+}
+
+impl OwnedGeometry {
+    pub fn geometry_type(&self) -> GeometryType {
+        match self {
+            OwnedGeometry::Point(_) => GeometryType::Point,
+            OwnedGeometry::LineString(_) => GeometryType::LineString,
+            OwnedGeometry::Polygon(_) => GeometryType::Polygon,
+            OwnedGeometry::MultiPoint(_) => GeometryType::MultiPoint,
+            OwnedGeometry::MultiLineString(_) => GeometryType::MultiLineString,
+            OwnedGeometry::MultiPolygon(_) => GeometryType::MultiPolygon,
+            OwnedGeometry::GeometryCollection(_) => GeometryType::GeometryCollection,
+            OwnedGeometry::CircularString(_) => GeometryType::CircularString,
+            OwnedGeometry::CompoundCurve(_) => GeometryType::CompoundCurve,
+            OwnedGeometry::CurvePolygon(_) => GeometryType::CurvePolygon,
+            OwnedGeometry::MultiCurve(_) => GeometryType::MultiCurve,
+            OwnedGeometry::MultiSurface(_) => GeometryType::MultiSurface,
+            OwnedGeometry::PolyhedralSurface(_) => GeometryType::PolyhedralSurface,
+            OwnedGeometry::TIN(_) => GeometryType::TIN,
+            OwnedGeometry::Triangle(_) => GeometryType::Triangle,
+        }
+    }
+
+    /// Point in polygon test for Polygon and MultiPolygon geometries (even-odd rule).
+    /// Returns false for other geometry types.
+    pub fn contains_point(&self, x: f64, y: f64) -> bool {
+        match self {
+            OwnedGeometry::Polygon(rings) => rings_contain_point(rings, x, y),
+            OwnedGeometry::MultiPolygon(polygons) => polygons
+                .iter()
+                .any(|rings| rings_contain_point(rings, x, y)),
+            _ => false,
+        }
+    }
+
+    /// Encode as FlatGeobuf geometry
+    pub fn to_fbs<'a>(&self, fbb: &mut FlatBufferBuilder<'a>) -> WIPOffset<Geometry<'a>> {
+        let geometry_type = self.geometry_type();
+        match self {
+            OwnedGeometry::Point(coord) => create_coords(fbb, &[*coord], None, geometry_type),
+            OwnedGeometry::LineString(coords)
+            | OwnedGeometry::MultiPoint(coords)
+            | OwnedGeometry::CircularString(coords)
+            | OwnedGeometry::Triangle(coords) => create_coords(fbb, coords, None, geometry_type),
+            OwnedGeometry::Polygon(rings)
+            | OwnedGeometry::MultiLineString(rings)
+            | OwnedGeometry::TIN(rings) => create_rings(fbb, rings, geometry_type),
+            OwnedGeometry::MultiPolygon(polygons) | OwnedGeometry::PolyhedralSurface(polygons) => {
+                let parts: Vec<_> = polygons
+                    .iter()
+                    .map(|rings| create_rings(fbb, rings, GeometryType::Polygon))
+                    .collect();
+                create_parts(fbb, &parts, geometry_type)
+            }
+            OwnedGeometry::GeometryCollection(geometries)
+            | OwnedGeometry::CompoundCurve(geometries)
+            | OwnedGeometry::CurvePolygon(geometries)
+            | OwnedGeometry::MultiCurve(geometries)
+            | OwnedGeometry::MultiSurface(geometries) => {
+                let parts: Vec<_> = geometries.iter().map(|g| g.to_fbs(fbb)).collect();
+                create_parts(fbb, &parts, geometry_type)
+            }
+        }
+    }
+}
+
+fn rings_contain_point(rings: &[Vec<Coordinate>], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    for ring in rings {
+        for (i, a) in ring.iter().enumerate() {
+            let b = &ring[(i + 1) % ring.len()];
+            if (a.y > y) != (b.y > y) && x < (b.x - a.x) * (y - a.y) / (b.y - a.y) + a.x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+fn create_coords<'a>(
+    fbb: &mut FlatBufferBuilder<'a>,
+    coords: &[Coordinate],
+    ends: Option<&[u32]>,
+    geometry_type: GeometryType,
+) -> WIPOffset<Geometry<'a>> {
+    let ends = ends.map(|ends| fbb.create_vector(ends));
+    let mut xy = Vec::with_capacity(coords.len() * 2);
+    for coord in coords {
+        xy.push(coord.x);
+        xy.push(coord.y);
+    }
+    let xy = Some(fbb.create_vector(&xy));
+    // Dimensions are written if any coordinate has a value
+    let mut dim = |value: fn(&Coordinate) -> Option<f64>| {
+        if coords.iter().any(|c| value(c).is_some()) {
+            let values: Vec<f64> = coords
+                .iter()
+                .map(|c| value(c).unwrap_or(f64::NAN))
+                .collect();
+            Some(fbb.create_vector(&values))
+        } else {
+            None
+        }
+    };
+    let z = dim(|c| c.z);
+    let m = dim(|c| c.m);
+    let t = dim(|c| c.t);
+    let tm = if coords.iter().any(|c| c.tm.is_some()) {
+        let values: Vec<u64> = coords.iter().map(|c| c.tm.unwrap_or(0)).collect();
+        Some(fbb.create_vector(&values))
+    } else {
+        None
+    };
+    Geometry::create(
+        fbb,
+        &GeometryArgs {
+            ends,
+            xy,
+            z,
+            m,
+            t,
+            tm,
+            type_: geometry_type,
+            parts: None,
+        },
+    )
+}
+
+fn create_rings<'a>(
+    fbb: &mut FlatBufferBuilder<'a>,
+    rings: &[Vec<Coordinate>],
+    geometry_type: GeometryType,
+) -> WIPOffset<Geometry<'a>> {
+    let coords: Vec<Coordinate> = rings.iter().flatten().cloned().collect();
+    if rings.len() > 1 {
+        let ends: Vec<u32> = rings
+            .iter()
+            .scan(0, |end, ring| {
+                *end += ring.len() as u32;
+                Some(*end)
+            })
+            .collect();
+        create_coords(fbb, &coords, Some(&ends), geometry_type)
+    } else {
+        create_coords(fbb, &coords, None, geometry_type)
+    }
+}
+
+fn create_parts<'a>(
+    fbb: &mut FlatBufferBuilder<'a>,
+    parts: &[WIPOffset<Geometry<'a>>],
+    geometry_type: GeometryType,
+) -> WIPOffset<Geometry<'a>> {
+    let parts = Some(fbb.create_vector(parts));
+    Geometry::create(
+        fbb,
+        &GeometryArgs {
+            parts,
+            type_: geometry_type,
+            ..Default::default()
+        },
+    )
+}
+
+#[cfg(test)]
+fn round_trip(geometry: &OwnedGeometry) -> Result<OwnedGeometry> {
+    let mut fbb = FlatBufferBuilder::new();
+    let geom = geometry.to_fbs(&mut fbb);
+    let feature = Feature::create(
+        &mut fbb,
+        &FeatureArgs {
+            geometry: Some(geom),
+            ..Default::default()
+        },
+    );
+    fbb.finish(feature, None);
+    let buf = fbb.finished_data();
+    let feature = root_as_feature(buf).unwrap();
+    feature
+        .geometry()
+        .unwrap()
+        .to_owned_geometry(GeometryType::Unknown)
+}
+
+#[test]
+fn polygon_round_trip() -> Result<()> {
+    let ring = |d: f64| {
+        vec![
+            Coordinate::xy(d, d),
+            Coordinate::xy(10.0 - d, d),
+            Coordinate::xy(10.0 - d, 10.0 - d),
+            Coordinate::xy(d, d),
+        ]
+    };
+    let polygon = OwnedGeometry::Polygon(vec![ring(0.0), ring(2.0)]);
+    assert_eq!(round_trip(&polygon)?, polygon);
+    let multipolygon =
+        OwnedGeometry::MultiPolygon(vec![vec![ring(0.0)], vec![ring(1.0), ring(2.0)]]);
+    assert_eq!(round_trip(&multipolygon)?, multipolygon);
+    let tin = OwnedGeometry::TIN(vec![ring(0.0), ring(1.0)]);
+    assert_eq!(round_trip(&tin)?, tin);
+    Ok(())
+}
+
+#[test]
+fn curve_round_trip() -> Result<()> {
+    let coord = |x: f64, y: f64| Coordinate {
+        z: Some(x + y),
+        tm: Some(42),
+        ..Coordinate::xy(x, y)
+    };
+    let compoundcurve = OwnedGeometry::CompoundCurve(vec![
+        OwnedGeometry::CircularString(vec![coord(0.0, 0.0), coord(1.0, 1.0), coord(2.0, 0.0)]),
+        OwnedGeometry::LineString(vec![coord(2.0, 0.0), coord(0.0, 0.0)]),
+    ]);
+    let geometry = OwnedGeometry::MultiSurface(vec![
+        OwnedGeometry::CurvePolygon(vec![compoundcurve]),
+        OwnedGeometry::Polygon(vec![vec![
+            coord(0.0, 0.0),
+            coord(1.0, 0.0),
+            coord(0.0, 0.0),
+        ]]),
+    ]);
+    assert_eq!(round_trip(&geometry)?, geometry);
+    Ok(())
+}
+
+#[test]
+fn polygon_contains_point() {
+    let ring = |d: f64| {
+        vec![
+            Coordinate::xy(d, d),
+            Coordinate::xy(10.0 - d, d),
+            Coordinate::xy(10.0 - d, 10.0 - d),
+            Coordinate::xy(d, 10.0 - d),
+            Coordinate::xy(d, d),
+        ]
+    };
+    let polygon = OwnedGeometry::Polygon(vec![ring(0.0), ring(4.0)]);
+    assert!(polygon.contains_point(1.0, 1.0));
+    assert!(!polygon.contains_point(5.0, 5.0)); // in hole
+    assert!(!polygon.contains_point(11.0, 5.0));
+    let multipolygon =
+        OwnedGeometry::MultiPolygon(vec![vec![ring(0.0), ring(4.0)], vec![ring(4.5)]]);
+    assert!(multipolygon.contains_point(5.0, 5.0));
+    assert!(!OwnedGeometry::LineString(ring(0.0)).contains_point(1.0, 1.0));
+}
@@ -2,17 +2,19 @@
 //! to enable fast bounding box spatial filtering.
 
 // use crate::http_reader::BufferedHttpClient;
+use crate::error::{Error, Result};
 use crate::http_client::BufferedHttpClient;
-use geozero::error::{GeozeroError, Result};
+use byteorder::{ByteOrder, LittleEndian};
 use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::{BinaryHeap, VecDeque};
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::mem::size_of;
 use std::{cmp, f64, u64, usize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 
 #[derive(Clone, PartialEq, Debug)]
 #[repr(C)]
-/// R-Tree node
+/// R-Tree node, serialized as 40 byte little endian record
 pub struct NodeItem {
     min_x: f64, // double
     min_y: f64, // double
@@ -43,6 +45,31 @@ impl NodeItem {
         }
     }
 
+    pub fn min_x(&self) -> f64 {
+        self.min_x
+    }
+
+    pub fn min_y(&self) -> f64 {
+        self.min_y
+    }
+
+    pub fn max_x(&self) -> f64 {
+        self.max_x
+    }
+
+    pub fn max_y(&self) -> f64 {
+        self.max_y
+    }
+
+    /// Byte offset in feature data section
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn set_offset(&mut self, offset: u64) {
+        self.offset = offset;
+    }
+
     pub fn width(&self) -> f64 {
         self.max_x - self.min_x
     }
@@ -51,12 +78,46 @@ impl NodeItem {
         self.max_y - self.min_y
     }
 
+    /// Area of box, 0 for empty boxes
+    pub fn area(&self) -> f64 {
+        if self.is_empty() {
+            0.0
+        } else {
+            self.width() * self.height()
+        }
+    }
+
+    /// True for boxes without extent like `NodeItem::create`
+    pub fn is_empty(&self) -> bool {
+        self.min_x > self.max_x || self.min_y > self.max_y
+    }
+
     pub fn sum(mut a: NodeItem, b: &NodeItem) -> NodeItem {
         a.expand(b);
         a
     }
 
-    fn expand(&mut self, r: &NodeItem) {
+    /// Box covering both boxes, keeping the offset of `self`
+    pub fn union(&self, r: &NodeItem) -> NodeItem {
+        NodeItem::sum(self.clone(), r)
+    }
+
+    /// Common part of both boxes, keeping the offset of `self`
+    pub fn intersection(&self, r: &NodeItem) -> Option<NodeItem> {
+        if !self.intersects(r) {
+            return None;
+        }
+        Some(NodeItem {
+            min_x: self.min_x.max(r.min_x),
+            min_y: self.min_y.max(r.min_y),
+            max_x: self.max_x.min(r.max_x),
+            max_y: self.max_y.min(r.max_y),
+            offset: self.offset,
+        })
+    }
+
+    /// Expand box in place to include `r`
+    pub fn expand(&mut self, r: &NodeItem) {
         if r.min_x < self.min_x {
             self.min_x = r.min_x;
         }
@@ -71,6 +132,11 @@ impl NodeItem {
         }
     }
 
+    /// Expand box in place to include point
+    pub fn expand_xy(&mut self, x: f64, y: f64) {
+        self.expand(&NodeItem::new(x, y, x, y))
+    }
+
     pub fn intersects(&self, r: &NodeItem) -> bool {
         if self.max_x < r.min_x {
             return false;
@@ -87,41 +153,105 @@ impl NodeItem {
         true
     }
 
-    // std::vector<double> NodeItem::toVector()
-    // {
-    //     return std::vector<double> { min_x, min_y, max_x, max_y };
-    // }
+    /// True if `r` lies completely within this box
+    pub fn contains(&self, r: &NodeItem) -> bool {
+        !r.is_empty()
+            && self.min_x <= r.min_x
+            && self.min_y <= r.min_y
+            && self.max_x >= r.max_x
+            && self.max_y >= r.max_y
+    }
+
+    /// Bounds as `[min_x, min_y, max_x, max_y]`
+    pub fn to_array(&self) -> [f64; 4] {
+        [self.min_x, self.min_y, self.max_x, self.max_y]
+    }
+}
+
+impl From<[f64; 4]> for NodeItem {
+    fn from(bounds: [f64; 4]) -> Self {
+        NodeItem::new(bounds[0], bounds[1], bounds[2], bounds[3])
+    }
 }
 
-/// Read full capacity of vec from data stream
-fn read_node_vec(node_items: &mut Vec<NodeItem>, data: &mut dyn Read) -> Result<()> {
-    let num_nodes = node_items.capacity();
-    let buf = unsafe {
-        std::slice::from_raw_parts_mut(
-            node_items.as_mut_ptr() as *mut u8,
-            num_nodes * size_of::<NodeItem>(),
-        )
-    };
-    data.read_exact(buf)?;
-    unsafe {
-        node_items.set_len(num_nodes);
+impl From<NodeItem> for [f64; 4] {
+    fn from(node: NodeItem) -> Self {
+        node.to_array()
+    }
+}
+
+/// Number of nodes decoded per read or write
+const NODE_CHUNK_SIZE: usize = 4096;
+
+impl NodeItem {
+    /// Decode node from little endian record
+    pub(crate) fn from_bytes(raw: &[u8]) -> NodeItem {
+        NodeItem {
+            min_x: LittleEndian::read_f64(&raw[0..8]),
+            min_y: LittleEndian::read_f64(&raw[8..16]),
+            max_x: LittleEndian::read_f64(&raw[16..24]),
+            max_y: LittleEndian::read_f64(&raw[24..32]),
+            offset: LittleEndian::read_u64(&raw[32..40]),
+        }
+    }
+
+    /// Encode node as little endian record
+    pub(crate) fn write_bytes(&self, raw: &mut [u8]) {
+        LittleEndian::write_f64(&mut raw[0..8], self.min_x);
+        LittleEndian::write_f64(&mut raw[8..16], self.min_y);
+        LittleEndian::write_f64(&mut raw[16..24], self.max_x);
+        LittleEndian::write_f64(&mut raw[24..32], self.max_y);
+        LittleEndian::write_u64(&mut raw[32..40], self.offset);
+    }
+}
+
+/// Read `num_nodes` node items from data stream
+fn read_node_vec(data: &mut dyn Read, num_nodes: usize) -> Result<Vec<NodeItem>> {
+    let mut node_items = Vec::new();
+    read_node_vec_into(data, num_nodes, &mut Vec::new(), &mut node_items)?;
+    Ok(node_items)
+}
+
+/// Read `num_nodes` node items from data stream into `node_items`.
+/// `buf` is used for decoding and can be reused between calls to avoid allocations.
+fn read_node_vec_into(
+    data: &mut dyn Read,
+    num_nodes: usize,
+    buf: &mut Vec<u8>,
+    node_items: &mut Vec<NodeItem>,
+) -> Result<()> {
+    node_items.clear();
+    node_items.reserve(num_nodes);
+    buf.resize(
+        cmp::min(num_nodes, NODE_CHUNK_SIZE) * size_of::<NodeItem>(),
+        0,
+    );
+    while node_items.len() < num_nodes {
+        let n = cmp::min(num_nodes - node_items.len(), NODE_CHUNK_SIZE);
+        let chunk = &mut buf[..n * size_of::<NodeItem>()];
+        data.read_exact(chunk)?;
+        node_items.extend(
+            chunk
+                .chunks_exact(size_of::<NodeItem>())
+                .map(NodeItem::from_bytes),
+        );
     }
     Ok(())
 }
 
-/// Read partial item vec from data stream
+/// Read partial item vec from data stream into `node_items`
 fn read_node_items<R: Read + Seek>(
     data: &mut R,
     base: u64,
     node_index: usize,
     length: usize,
-) -> Result<Vec<NodeItem>> {
-    let mut node_items = Vec::with_capacity(length);
+    buf: &mut Vec<u8>,
+    node_items: &mut Vec<NodeItem>,
+) -> Result<()> {
     data.seek(SeekFrom::Start(
         base + (node_index * size_of::<NodeItem>()) as u64,
     ))?;
-    read_node_vec(&mut node_items, data)?;
-    Ok(node_items)
+    read_node_vec_into(data, length, buf, node_items)
 }
 
 /// Read partial item vec from http
@@ -135,14 +265,45 @@ async fn read_http_node_items(
     let begin = base + node_index * size_of::<NodeItem>();
     let len = length * size_of::<NodeItem>();
     let bytes = client.get(begin, len, min_req_size).await?;
+    Ok(node_items_from_bytes(bytes, length))
+}
+
+/// Read partial item vec from async data stream
+async fn read_async_node_items<R: AsyncRead + AsyncSeek + Unpin>(
+    data: &mut R,
+    base: u64,
+    node_index: usize,
+    length: usize,
+) -> Result<Vec<NodeItem>> {
+    data.seek(SeekFrom::Start(
+        base + (node_index * size_of::<NodeItem>()) as u64,
+    ))
+    .await?;
+    let mut buf = vec![0; length * size_of::<NodeItem>()];
+    data.read_exact(&mut buf).await?;
+    Ok(node_items_from_bytes(&buf, length))
+}
+
+/// Decode `length` node items from raw bytes
+fn node_items_from_bytes(bytes: &[u8], length: usize) -> Vec<NodeItem> {
+    bytes[..length * size_of::<NodeItem>()]
+        .chunks_exact(size_of::<NodeItem>())
+        .map(NodeItem::from_bytes)
+        .collect()
+}
 
-    let mut node_items = Vec::with_capacity(length);
-    let buf = unsafe { std::slice::from_raw_parts_mut(node_items.as_mut_ptr() as *mut u8, len) };
-    buf.clone_from_slice(&bytes);
-    unsafe {
-        node_items.set_len(length);
+/// Check that internal node `node_index` references its children within the level `children`
+fn check_child_offset(node_index: usize, node: &NodeItem, children: (usize, usize)) -> Result<()> {
+    let (min, max) = children;
+    if node.offset < min as u64 || node.offset >= max as u64 {
+        return Err(Error::IndexChildOffset {
+            node: node_index,
+            offset: node.offset,
+            min,
+            max,
+        });
     }
-    Ok(node_items)
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -152,9 +313,272 @@ pub struct SearchResultItem {
     pub offset: usize,
     /// Feature number
     pub index: usize,
+    /// Bounds of leaf node
+    pub bbox: NodeItem,
+}
+
+/// Index traversal state of stream searches.
+/// Nodes are visited in storage order to make index reads sequential,
+/// or depth-first to reach the first leaves early.
+struct StreamSearch {
+    bbox: NodeItem,
+    level_bounds: Vec<(usize, usize)>,
+    leaf_nodes_offset: usize,
+    num_nodes: usize,
+    node_size: usize,
+    /// search queue of (node index, level)
+    queue: SearchQueue,
+}
+
+/// Pending nodes of a search as (node index, level)
+enum SearchQueue {
+    /// Smallest node index first. Holds all matching nodes of a level.
+    StorageOrder(BinaryHeap<Reverse<(usize, usize)>>),
+    /// Stack with the next node on top. Holds at most one node per level and their siblings.
+    DepthFirst(Vec<(usize, usize)>),
+}
+
+impl SearchQueue {
+    fn push(&mut self, node: (usize, usize)) {
+        match self {
+            SearchQueue::StorageOrder(heap) => heap.push(Reverse(node)),
+            SearchQueue::DepthFirst(stack) => stack.push(node),
+        }
+    }
+
+    fn pop(&mut self) -> Option<(usize, usize)> {
+        match self {
+            SearchQueue::StorageOrder(heap) => heap.pop().map(|Reverse(node)| node),
+            SearchQueue::DepthFirst(stack) => stack.pop(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            SearchQueue::StorageOrder(heap) => heap.len(),
+            SearchQueue::DepthFirst(stack) => stack.len(),
+        }
+    }
+
+    /// Put the nodes pushed since the queue had `len` entries into visiting order
+    fn finish_children(&mut self, len: usize) {
+        if let SearchQueue::DepthFirst(stack) = self {
+            stack[len..].reverse();
+        }
+    }
+}
+
+impl StreamSearch {
+    fn new(
+        num_items: usize,
+        node_size: u16,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> Result<StreamSearch> {
+        let level_bounds = PackedRTree::generate_level_bounds(num_items, node_size)?;
+        let (leaf_nodes_offset, num_nodes) = level_bounds[0];
+        let mut queue = SearchQueue::StorageOrder(BinaryHeap::new());
+        queue.push((0, level_bounds.len() - 1));
+        Ok(StreamSearch {
+            bbox: NodeItem::new(min_x, min_y, max_x, max_y),
+            level_bounds,
+            leaf_nodes_offset,
+            num_nodes,
+            node_size: node_size as usize,
+            queue,
+        })
+    }
+
+    /// Visit nodes depth-first instead of in storage order
+    fn depth_first(mut self) -> Self {
+        let mut stack = Vec::new();
+        while let Some(node) = self.queue.pop() {
+            stack.push(node);
+        }
+        stack.reverse();
+        self.queue = SearchQueue::DepthFirst(stack);
+        self
+    }
+
+    /// Next node to read as (node index, end index, level)
+    fn next_node(&mut self) -> Option<(usize, usize, usize)> {
+        let (node_index, level) = self.queue.pop()?;
+        // find the end index of the node
+        let end = cmp::min(node_index + self.node_size, self.level_bounds[level].1);
+        Some((node_index, end, level))
+    }
+
+    /// Check child offsets of nodes read for node returned by `next_node`
+    fn check(&self, node_index: usize, level: usize, node_items: &[NodeItem]) -> Result<()> {
+        if level == 0 {
+            return Ok(());
+        }
+        for (node_pos, node_item) in node_items.iter().enumerate() {
+            check_child_offset(
+                node_index + node_pos,
+                node_item,
+                self.level_bounds[level - 1],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Search through child nodes of node returned by `next_node`
+    fn visit<E: Extend<SearchResultItem>>(
+        &mut self,
+        node_index: usize,
+        level: usize,
+        node_items: &[NodeItem],
+        results: &mut E,
+    ) {
+        let is_leaf_node = node_index >= self.leaf_nodes_offset;
+        let queue_len = self.queue.len();
+        for (node_pos, node_item) in node_items.iter().enumerate() {
+            if !self.bbox.intersects(&node_item) {
+                continue;
+            }
+            if is_leaf_node {
+                results.extend(Some(SearchResultItem {
+                    offset: node_item.offset as usize,
+                    index: node_index + node_pos - self.leaf_nodes_offset,
+                    bbox: node_item.clone(),
+                }));
+            } else {
+                self.queue.push((node_item.offset as usize, level - 1));
+            }
+        }
+        self.queue.finish_children(queue_len);
+    }
+}
+
+/// Lazy bbox search in a serialized index.
+///
+/// Index nodes are read on demand and leaf hits are returned in file order
+/// while the index is traversed, without collecting all results first.
+///
+/// Searches created with `new` read the nodes in storage order. Because the index is stored
+/// top-down, all matching internal nodes are read before the first leaf and memory grows with
+/// the number of hits divided by the node size. Searches created with `depth_first` return
+/// the first hit after reading one node per level and keep at most `node_size` nodes per level.
+pub struct IndexSearch {
+    search: StreamSearch,
+    index_base: u64,
+    pending: VecDeque<SearchResultItem>,
+    /// Read buffer, reused for all nodes
+    buf: Vec<u8>,
+    /// Nodes of the last read, reused for all nodes
+    node_items: Vec<NodeItem>,
+}
+
+impl IndexSearch {
+    /// Prepare search in index starting at byte offset `index_base`
+    pub fn new(
+        index_base: u64,
+        num_items: usize,
+        node_size: u16,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> Result<IndexSearch> {
+        Ok(IndexSearch {
+            search: StreamSearch::new(num_items, node_size, min_x, min_y, max_x, max_y)?,
+            index_base,
+            pending: VecDeque::new(),
+            buf: Vec::new(),
+            node_items: Vec::new(),
+        })
+    }
+
+    /// Prepare depth-first search in index starting at byte offset `index_base`
+    pub fn depth_first(
+        index_base: u64,
+        num_items: usize,
+        node_size: u16,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> Result<IndexSearch> {
+        let search =
+            IndexSearch::new(index_base, num_items, node_size, min_x, min_y, max_x, max_y)?;
+        Ok(IndexSearch {
+            search: search.search.depth_first(),
+            ..search
+        })
+    }
+
+    /// Next search hit, reading index nodes from `data` as needed
+    pub fn next_item<R: Read + Seek>(&mut self, data: &mut R) -> Result<Option<SearchResultItem>> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Ok(Some(item));
+            }
+            match self.search.next_node() {
+                Some((node_index, end, level)) => {
+                    read_node_items(
+                        data,
+                        self.index_base,
+                        node_index,
+                        end - node_index,
+                        &mut self.buf,
+                        &mut self.node_items,
+                    )?;
+                    self.search.check(node_index, level, &self.node_items)?;
+                    self.search
+                        .visit(node_index, level, &self.node_items, &mut self.pending);
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Byte offset of the end of the index, which is the start of the feature data section
+    pub fn index_end(&self) -> u64 {
+        self.index_base + (self.search.num_nodes * size_of::<NodeItem>()) as u64
+    }
+}
+
+/// Iterator over search hits in a serialized index. See `PackedRTree::stream_search_iter`.
+pub struct StreamSearchIter<R> {
+    data: R,
+    search: IndexSearch,
+}
+
+impl<R: Read + Seek> Iterator for StreamSearchIter<R> {
+    type Item = Result<SearchResultItem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.search.next_item(&mut self.data).transpose()
+    }
+}
+
+/// Iterator over search hits in an in-memory index. See `PackedRTree::search_iter`.
+pub struct SearchIter<'a> {
+    node_items: &'a [NodeItem],
+    search: StreamSearch,
+    pending: VecDeque<SearchResultItem>,
+}
+
+impl Iterator for SearchIter<'_> {
+    type Item = SearchResultItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Some(item);
+            }
+            let (node_index, end, level) = self.search.next_node()?;
+            let node_items = &self.node_items[node_index..end];
+            self.search
+                .visit(node_index, level, node_items, &mut self.pending);
+        }
+    }
 }
 
-const HILBERT_MAX: u32 = (1 << 16) - 1;
+pub(crate) const HILBERT_MAX: u32 = (1 << 16) - 1;
 
 // Based on public domain code at https://github.com/rawrunprotected/hilbert_curves
 fn hilbert(x: u32, y: u32) -> u32 {
@@ -214,15 +638,107 @@ fn hilbert(x: u32, y: u32) -> u32 {
     value
 }
 
-fn hilbert_bbox(r: &NodeItem, hilbert_max: u32, extent: &NodeItem) -> u32 {
-    // calculate bbox center and scale to hilbert_max
-    let x = (hilbert_max as f64 * ((r.min_x + r.max_x) / 2.0 - extent.min_x) / extent.width())
-        .floor() as u32;
-    let y = (hilbert_max as f64 * ((r.min_y + r.max_y) / 2.0 - extent.min_y) / extent.height())
-        .floor() as u32;
+// 32 bit per axis variant of `hilbert`
+fn hilbert64(x: u32, y: u32) -> u64 {
+    const MASK: u64 = 0xFFFF_FFFF;
+    let x = x as u64;
+    let y = y as u64;
+    let mut a = x ^ y;
+    let mut b = MASK ^ a;
+    let mut c = MASK ^ (x | y);
+    let mut d = x & (y ^ MASK);
+
+    let mut aa = a | (b >> 1);
+    let mut bb = (a >> 1) ^ a;
+    let mut cc = ((c >> 1) ^ (b & (d >> 1))) ^ c;
+    let mut dd = ((a & (c >> 1)) ^ (d >> 1)) ^ d;
+
+    for shift in &[2, 4, 8] {
+        a = aa;
+        b = bb;
+        c = cc;
+        d = dd;
+        aa = (a & (a >> shift)) ^ (b & (b >> shift));
+        bb = (a & (b >> shift)) ^ (b & ((a ^ b) >> shift));
+        cc ^= (a & (c >> shift)) ^ (b & (d >> shift));
+        dd ^= (b & (c >> shift)) ^ ((a ^ b) & (d >> shift));
+    }
+
+    a = aa;
+    b = bb;
+    c = cc;
+    d = dd;
+    cc ^= (a & (c >> 16)) ^ (b & (d >> 16));
+    dd ^= (b & (c >> 16)) ^ ((a ^ b) & (d >> 16));
+
+    a = cc ^ (cc >> 1);
+    b = dd ^ (dd >> 1);
+
+    let i0 = x ^ y;
+    let i1 = b | (MASK ^ (i0 | a));
+
+    (interleave(i1) << 1) | interleave(i0)
+}
+
+/// Spread the lower 32 bits of `v` to the even bits
+fn interleave(v: u64) -> u64 {
+    let mut v = v & 0xFFFF_FFFF;
+    v = (v | (v << 16)) & 0x0000_FFFF_0000_FFFF;
+    v = (v | (v << 8)) & 0x00FF_00FF_00FF_00FF;
+    v = (v | (v << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+    v = (v | (v << 1)) & 0x5555_5555_5555_5555;
+    v
+}
+
+/// bbox center scaled to 0..=max
+fn scaled_center(r: &NodeItem, max: u32, extent: &NodeItem) -> (u32, u32) {
+    let x =
+        (max as f64 * ((r.min_x + r.max_x) / 2.0 - extent.min_x) / extent.width()).floor() as u32;
+    let y =
+        (max as f64 * ((r.min_y + r.max_y) / 2.0 - extent.min_y) / extent.height()).floor() as u32;
+    (x, y)
+}
+
+pub(crate) fn hilbert_bbox(r: &NodeItem, hilbert_max: u32, extent: &NodeItem) -> u32 {
+    let (x, y) = scaled_center(r, hilbert_max, extent);
     hilbert(x, y)
 }
 
+/// Space-filling curve used for ordering index items
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SpaceFillingCurve {
+    /// Hilbert curve with 16 bit per axis, as used by other FlatGeobuf implementations
+    Hilbert16,
+    /// Hilbert curve with 32 bit per axis, for very dense datasets
+    Hilbert32,
+    /// Z-order (Morton) curve with 32 bit per axis
+    ZOrder,
+}
+
+impl Default for SpaceFillingCurve {
+    fn default() -> Self {
+        SpaceFillingCurve::Hilbert16
+    }
+}
+
+impl SpaceFillingCurve {
+    /// Curve index of bbox center within `extent`
+    pub fn index(&self, r: &NodeItem, extent: &NodeItem) -> u64 {
+        match self {
+            SpaceFillingCurve::Hilbert16 => hilbert_bbox(r, HILBERT_MAX, extent) as u64,
+            SpaceFillingCurve::Hilbert32 => {
+                let (x, y) = scaled_center(r, u32::MAX, extent);
+                hilbert64(x, y)
+            }
+            SpaceFillingCurve::ZOrder => {
+                let (x, y) = scaled_center(r, u32::MAX, extent);
+                (interleave(y as u64) << 1) | interleave(x as u64)
+            }
+        }
+    }
+}
+
 pub fn hilbert_sort(items: &mut Vec<NodeItem>) {
     let extent = calc_extent(items);
     items.sort_by(|a, b| {
@@ -232,6 +748,18 @@ pub fn hilbert_sort(items: &mut Vec<NodeItem>) {
     });
 }
 
+/// Sort items along `curve`. `SpaceFillingCurve::Hilbert16` gives the same order as `hilbert_sort`.
+pub fn sort_by_curve(items: &mut Vec<NodeItem>, curve: SpaceFillingCurve) {
+    let extent = calc_extent(items);
+    let mut keyed: Vec<(u64, NodeItem)> = items
+        .drain(..)
+        .map(|item| (curve.index(&item, &extent), item))
+        .collect();
+    // stable sort, descending like `hilbert_sort`
+    keyed.sort_by(|a, b| b.0.cmp(&a.0));
+    items.extend(keyed.into_iter().map(|(_, item)| item));
+}
+
 // void hilbert_sort_shared_ptr(std::vector<std::shared_ptr<Item>> &items)
 // {
 //     NodeItem extent = std::accumulate(items.begin(), items.end(), NodeItem::create(0), [] (NodeItem a, std::shared_ptr<Item> b) {
@@ -261,6 +789,28 @@ pub fn calc_extent(nodes: &Vec<NodeItem>) -> NodeItem {
 //     return extent;
 // }
 
+/// Check index node size and number of items
+fn check_index_params(num_items: usize, node_size: u16) -> Result<()> {
+    if node_size < 2 {
+        return Err(Error::IndexNodeSize(node_size));
+    }
+    if num_items == 0 {
+        return Err(Error::IndexEmpty);
+    }
+    if num_items > usize::MAX - ((num_items / node_size as usize) * 2) {
+        return Err(Error::IndexTooLarge(num_items));
+    }
+    Ok(())
+}
+
+/// Size of the index of a dataset, which is 0 for datasets without index
+pub(crate) fn dataset_index_size(num_items: usize, node_size: u16) -> Result<usize> {
+    if node_size == 0 || num_items == 0 {
+        return Ok(0);
+    }
+    PackedRTree::index_size(num_items, node_size)
+}
+
 /// Packed Hilbert R-Tree
 pub struct PackedRTree {
     extent: NodeItem,
@@ -275,26 +825,18 @@ impl PackedRTree {
     pub const DEFAULT_NODE_SIZE: u16 = 16;
 
     fn init(&mut self, node_size: u16) -> Result<()> {
-        assert!(node_size >= 2, "Node size must be at least 2");
-        assert!(self.num_items > 0, "Cannot create empty tree");
-        self.node_size = cmp::min(cmp::max(node_size, 2u16), 65535u16);
-        self.level_bounds = PackedRTree::generate_level_bounds(self.num_items, self.node_size);
-        self.num_nodes = self
-            .level_bounds
-            .first()
-            .ok_or(GeozeroError::GeometryIndex)?
-            .1;
+        self.node_size = node_size;
+        self.level_bounds = PackedRTree::generate_level_bounds(self.num_items, self.node_size)?;
+        self.num_nodes = self.level_bounds[0].1;
         self.node_items = vec![NodeItem::create(0); self.num_nodes]; // Quite slow!
         Ok(())
     }
 
-    fn generate_level_bounds(num_items: usize, node_size: u16) -> Vec<(usize, usize)> {
-        assert!(node_size >= 2, "Node size must be at least 2");
-        assert!(num_items > 0, "Cannot create empty tree");
-        assert!(
-            num_items <= usize::MAX - ((num_items / node_size as usize) * 2),
-            "Number of items too large"
-        );
+    pub(crate) fn generate_level_bounds(
+        num_items: usize,
+        node_size: u16,
+    ) -> Result<Vec<(usize, usize)>> {
+        check_index_params(num_items, node_size)?;
 
         // number of nodes per level in bottom-up order
         let mut level_num_nodes: Vec<usize> = Vec::new();
@@ -323,7 +865,7 @@ impl PackedRTree {
             level_bounds.push((level_offsets[i], level_offsets[i] + level_num_nodes[i]));
         }
         level_bounds.reverse();
-        level_bounds
+        Ok(level_bounds)
     }
 
     fn generate_nodes(&mut self) {
@@ -347,29 +889,45 @@ impl PackedRTree {
     }
 
     fn read_data(&mut self, data: &mut dyn Read) -> Result<()> {
-        read_node_vec(&mut self.node_items, data)?;
+        self.node_items = read_node_vec(data, self.num_nodes)?;
         for node in &self.node_items {
             self.extent.expand(&node)
         }
         Ok(())
     }
 
+    /// Check child offsets of loaded internal nodes
+    fn check_nodes(&self) -> Result<()> {
+        for level in 1..self.level_bounds.len() {
+            let (start, end) = self.level_bounds[level];
+            for node_index in start..cmp::min(end, self.node_items.len()) {
+                check_child_offset(
+                    node_index,
+                    &self.node_items[node_index],
+                    self.level_bounds[level - 1],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
     async fn read_http(
         &mut self,
         client: &mut BufferedHttpClient,
         index_begin: usize,
+        num_nodes: usize,
     ) -> Result<()> {
-        let min_req_size = self.size(); // read full index at once
-        let mut pos = index_begin;
-        for i in 0..self.num_nodes {
-            let bytes = client.get(pos, size_of::<NodeItem>(), min_req_size).await?;
-            let p = bytes.as_ptr() as *const [u8; size_of::<NodeItem>()];
-            let n: NodeItem = unsafe { std::mem::transmute(*p) };
-            self.node_items[i] = n.clone();
-            self.extent.expand(&n);
-            pos += size_of::<NodeItem>();
+        if num_nodes == 0 {
+            return Ok(());
         }
-        Ok(())
+        // read requested levels at once
+        let min_req_size = num_nodes * size_of::<NodeItem>();
+        self.node_items =
+            read_http_node_items(client, min_req_size, index_begin, 0, num_nodes).await?;
+        for node in &self.node_items {
+            self.extent.expand(&node)
+        }
+        self.check_nodes()
     }
 
     pub fn build(nodes: &Vec<NodeItem>, extent: &NodeItem, node_size: u16) -> Result<PackedRTree> {
@@ -383,19 +941,29 @@ impl PackedRTree {
         };
         tree.init(node_size)?;
         for i in 0..tree.num_items {
-            tree.node_items[(tree.num_nodes - tree.num_items + i)] = nodes[i].clone();
+            tree.node_items[tree.num_nodes - tree.num_items + i] = nodes[i].clone();
         }
         tree.generate_nodes();
         Ok(tree)
     }
 
     pub fn from_buf(data: &mut dyn Read, num_items: usize, node_size: u16) -> Result<PackedRTree> {
-        let node_size = cmp::min(cmp::max(node_size, 2u16), 65535u16);
-        let level_bounds = PackedRTree::generate_level_bounds(num_items, node_size);
-        let num_nodes = level_bounds.first().ok_or(GeozeroError::GeometryIndex)?.1;
+        let tree = PackedRTree::from_buf_unchecked(data, num_items, node_size)?;
+        tree.check_nodes()?;
+        Ok(tree)
+    }
+
+    /// Read index without checking child offsets of internal nodes
+    pub(crate) fn from_buf_unchecked(
+        data: &mut dyn Read,
+        num_items: usize,
+        node_size: u16,
+    ) -> Result<PackedRTree> {
+        let level_bounds = PackedRTree::generate_level_bounds(num_items, node_size)?;
+        let num_nodes = level_bounds[0].1;
         let mut tree = PackedRTree {
             extent: NodeItem::create(0),
-            node_items: Vec::with_capacity(num_nodes),
+            node_items: Vec::new(),
             num_items,
             num_nodes,
             node_size,
@@ -405,25 +973,60 @@ impl PackedRTree {
         Ok(tree)
     }
 
+    /// Read extent of serialized index from its root node
+    pub fn read_extent(data: &mut dyn Read) -> Result<NodeItem> {
+        let mut buf = [0; size_of::<NodeItem>()];
+        data.read_exact(&mut buf)?;
+        let root = node_items_from_bytes(&buf, 1).remove(0);
+        // bounds only, without the child offset of the root node
+        Ok(NodeItem::new(root.min_x, root.min_y, root.max_x, root.max_y))
+    }
+
     pub async fn from_http(
         client: &mut BufferedHttpClient,
         index_begin: usize,
         num_items: usize,
         node_size: u16,
     ) -> Result<PackedRTree> {
+        PackedRTree::from_http_levels(client, index_begin, num_items, node_size, usize::MAX).await
+    }
+
+    /// Read the upper `levels` levels of the index. Lower levels are read on demand by `http_search`.
+    pub async fn from_http_levels(
+        client: &mut BufferedHttpClient,
+        index_begin: usize,
+        num_items: usize,
+        node_size: u16,
+        levels: usize,
+    ) -> Result<PackedRTree> {
+        let level_bounds = PackedRTree::generate_level_bounds(num_items, node_size)?;
+        let num_nodes = level_bounds[0].1;
+        // levels are stored top-down, so the upper levels are a prefix of the index
+        let num_loaded = if levels >= level_bounds.len() {
+            num_nodes
+        } else if levels == 0 {
+            0
+        } else {
+            level_bounds[level_bounds.len() - levels].1
+        };
         let mut tree = PackedRTree {
             extent: NodeItem::create(0),
             node_items: Vec::new(),
-            num_items: num_items,
-            num_nodes: 0,
-            node_size: 0,
-            level_bounds: Vec::new(),
+            num_items,
+            num_nodes,
+            node_size,
+            level_bounds,
         };
-        tree.init(node_size)?;
-        tree.read_http(client, index_begin).await?;
+        tree.read_http(client, index_begin, num_loaded).await?;
         Ok(tree)
     }
 
+    /// Returns true, if all index levels are in memory
+    pub fn is_loaded(&self) -> bool {
+        self.node_items.len() == self.num_nodes
+    }
+
+    /// Search in loaded index. Results are in feature offset order, like `stream_search`.
     pub fn search(
         &self,
         min_x: f64,
@@ -431,45 +1034,87 @@ impl PackedRTree {
         max_x: f64,
         max_y: f64,
     ) -> Result<Vec<SearchResultItem>> {
-        let leaf_nodes_offset = self
-            .level_bounds
-            .first()
-            .ok_or(GeozeroError::GeometryIndex)?
-            .0;
-        let n = NodeItem::new(min_x, min_y, max_x, max_y);
+        Ok(self.search_iter(min_x, min_y, max_x, max_y)?.collect())
+    }
+
+    /// Leaf node of feature number `index`. Returns `None` if the leaf level is not loaded.
+    pub fn leaf_node(&self, index: usize) -> Option<&NodeItem> {
+        if !self.is_loaded() || index >= self.num_items {
+            return None;
+        }
+        self.node_items.get(self.level_bounds[0].0 + index)
+    }
+
+    /// Lazy search in loaded index. Hits are returned in offset order.
+    pub fn search_iter(
+        &self,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> Result<SearchIter<'_>> {
+        if !self.is_loaded() {
+            return Err(Error::IndexNotLoaded);
+        }
+        Ok(SearchIter {
+            node_items: &self.node_items,
+            search: StreamSearch::new(self.num_items, self.node_size, min_x, min_y, max_x, max_y)?,
+            pending: VecDeque::new(),
+        })
+    }
+
+    /// Spatial join with another loaded index by traversing both trees at once.
+    /// Returns all pairs of intersecting leaf nodes, ordered by feature offset in `self`
+    /// and `other`.
+    pub fn join(&self, other: &PackedRTree) -> Result<Vec<(SearchResultItem, SearchResultItem)>> {
+        if !self.is_loaded() || !other.is_loaded() {
+            return Err(Error::IndexNotLoaded);
+        }
         let mut results = Vec::new();
-        let mut queue = HashMap::new(); // C++: std::unordered_map
-        queue.insert(0, self.level_bounds.len() - 1);
-        while queue.len() != 0 {
-            let next = queue.iter().next().ok_or(GeozeroError::GeometryIndex)?;
-            let node_index = *next.0;
-            let level = *next.1;
-            queue.remove(&node_index);
-            let is_leaf_node = node_index >= self.num_nodes - self.num_items;
-            // find the end index of the node
-            let end = cmp::min(
-                node_index + self.node_size as usize,
-                self.level_bounds[level].1,
-            );
-            // search through child nodes
-            for pos in node_index..end {
-                let node_item = &self.node_items[pos];
-                if !n.intersects(&node_item) {
-                    continue;
+        // node pairs as (node index, level) of both trees
+        let mut stack = vec![(
+            (0, self.level_bounds.len() - 1),
+            (0, other.level_bounds.len() - 1),
+        )];
+        while let Some(((a, level_a), (b, level_b))) = stack.pop() {
+            if !self.node_items[a].intersects(&other.node_items[b]) {
+                continue;
+            }
+            if level_a == 0 && level_b == 0 {
+                results.push((self.leaf_result(a), other.leaf_result(b)));
+            } else if level_b == 0 || (level_a > 0 && level_a >= level_b) {
+                for child in self.child_nodes(a, level_a) {
+                    stack.push(((child, level_a - 1), (b, level_b)));
                 }
-                if is_leaf_node {
-                    results.push(SearchResultItem {
-                        offset: node_item.offset as usize,
-                        index: pos - leaf_nodes_offset,
-                    });
-                } else {
-                    queue.insert(node_item.offset as usize, level - 1);
+            } else {
+                for child in other.child_nodes(b, level_b) {
+                    stack.push(((a, level_a), (child, level_b - 1)));
                 }
             }
         }
+        results.sort_by_key(|(a, b)| (a.offset, b.offset));
         Ok(results)
     }
 
+    /// Index range of child nodes of internal node
+    fn child_nodes(&self, node_index: usize, level: usize) -> std::ops::Range<usize> {
+        let start = self.node_items[node_index].offset as usize;
+        let end = cmp::min(
+            start + self.node_size as usize,
+            self.level_bounds[level - 1].1,
+        );
+        start..end
+    }
+
+    fn leaf_result(&self, node_index: usize) -> SearchResultItem {
+        let node_item = &self.node_items[node_index];
+        SearchResultItem {
+            offset: node_item.offset as usize,
+            index: node_index - self.level_bounds[0].0,
+            bbox: node_item.clone(),
+        }
+    }
+
     pub fn stream_search<R: Read + Seek>(
         data: &mut R,
         num_items: usize,
@@ -479,49 +1124,64 @@ impl PackedRTree {
         max_x: f64,
         max_y: f64,
     ) -> Result<Vec<SearchResultItem>> {
-        let item = NodeItem::new(min_x, min_y, max_x, max_y);
-        let level_bounds = PackedRTree::generate_level_bounds(num_items, node_size);
-        let leaf_nodes_offset = level_bounds.first().ok_or(GeozeroError::GeometryIndex)?.0;
-        let num_nodes = level_bounds.first().ok_or(GeozeroError::GeometryIndex)?.1;
-
         // current position must be start of index
         let index_base = data.seek(SeekFrom::Current(0))?;
+        let mut search =
+            IndexSearch::new(index_base, num_items, node_size, min_x, min_y, max_x, max_y)?;
 
-        // use ordered search queue to make index traversal in sequential order
-        let mut queue = BinaryHeap::new();
-        queue.push(Reverse((0, level_bounds.len() - 1)));
         let mut results = Vec::new();
+        while let Some(item) = search.next_item(data)? {
+            results.push(item);
+        }
+        // Skip rest of index
+        data.seek(SeekFrom::Start(search.index_end()))?;
+        Ok(results)
+    }
 
-        while queue.len() != 0 {
-            let next = queue.pop().ok_or(GeozeroError::GeometryIndex)?.0;
-            let node_index = next.0;
-            let level = next.1;
-            let is_leaf_node = node_index >= num_nodes - num_items;
-            // find the end index of the node
-            let end = cmp::min(node_index + node_size as usize, level_bounds[level].1);
-            let length = end - node_index;
-            let node_items = read_node_items(data, index_base, node_index, length)?;
-            // search through child nodes
-            for pos in node_index..end {
-                let node_pos = pos - node_index;
-                let node_item = &node_items[node_pos];
-                if !item.intersects(&node_item) {
-                    continue;
-                }
-                if is_leaf_node {
-                    results.push(SearchResultItem {
-                        offset: node_item.offset as usize,
-                        index: pos - leaf_nodes_offset,
-                    });
-                } else {
-                    queue.push(Reverse((node_item.offset as usize, level - 1)));
-                }
-            }
+    /// Lazy search in serialized index starting at the current position of `data`.
+    /// Hits are returned in file order while the index is traversed.
+    pub fn stream_search_iter<R: Read + Seek>(
+        mut data: R,
+        num_items: usize,
+        node_size: u16,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> Result<StreamSearchIter<R>> {
+        let index_base = data.seek(SeekFrom::Current(0))?;
+        let search =
+            IndexSearch::new(index_base, num_items, node_size, min_x, min_y, max_x, max_y)?;
+        Ok(StreamSearchIter { data, search })
+    }
+
+    /// Search index in async data stream, starting at the current position
+    pub async fn async_stream_search<R: AsyncRead + AsyncSeek + Unpin>(
+        data: &mut R,
+        num_items: usize,
+        node_size: u16,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> Result<Vec<SearchResultItem>> {
+        let mut search = StreamSearch::new(num_items, node_size, min_x, min_y, max_x, max_y)?;
+
+        // current position must be start of index
+        let index_base = data.seek(SeekFrom::Current(0)).await?;
+
+        let mut results = Vec::new();
+        while let Some((node_index, end, level)) = search.next_node() {
+            let node_items =
+                read_async_node_items(data, index_base, node_index, end - node_index).await?;
+            search.check(node_index, level, &node_items)?;
+            search.visit(node_index, level, &node_items, &mut results);
         }
         // Skip rest of index
         data.seek(SeekFrom::Start(
-            index_base + (num_nodes * size_of::<NodeItem>()) as u64,
-        ))?;
+            index_base + (search.num_nodes * size_of::<NodeItem>()) as u64,
+        ))
+        .await?;
         Ok(results)
     }
 
@@ -535,44 +1195,45 @@ impl PackedRTree {
         max_x: f64,
         max_y: f64,
     ) -> Result<Vec<SearchResultItem>> {
-        let item = NodeItem::new(min_x, min_y, max_x, max_y);
-        let level_bounds = PackedRTree::generate_level_bounds(num_items, node_size);
-        let leaf_nodes_offset = level_bounds.first().ok_or(GeozeroError::GeometryIndex)?.0;
-        let num_nodes = level_bounds.first().ok_or(GeozeroError::GeometryIndex)?.1;
-
+        let tree =
+            PackedRTree::from_http_levels(client, index_begin, num_items, node_size, 0).await?;
         // read full index at once, if < 1MB
-        let min_req_size = cmp::min(num_nodes * size_of::<NodeItem>(), 1_048_576);
+        tree.http_search(client, index_begin, 1_048_576, min_x, min_y, max_x, max_y)
+            .await
+    }
 
-        // use ordered search queue to make index traversal in sequential order
-        let mut queue = BinaryHeap::new();
-        queue.push(Reverse((0, level_bounds.len() - 1)));
-        let mut results = Vec::new();
+    /// Search with index levels in memory, reading missing levels via HTTP
+    /// in requests of at least `chunk_size` bytes
+    pub async fn http_search(
+        &self,
+        client: &mut BufferedHttpClient,
+        index_begin: usize,
+        chunk_size: usize,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> Result<Vec<SearchResultItem>> {
+        let mut search =
+            StreamSearch::new(self.num_items, self.node_size, min_x, min_y, max_x, max_y)?;
+        let min_req_size = cmp::min(self.num_nodes * size_of::<NodeItem>(), chunk_size);
 
-        while queue.len() != 0 {
-            let next = queue.pop().ok_or(GeozeroError::GeometryIndex)?.0;
-            let node_index = next.0;
-            let level = next.1;
-            let is_leaf_node = node_index >= num_nodes - num_items;
-            // find the end index of the node
-            let end = cmp::min(node_index + node_size as usize, level_bounds[level].1);
-            let length = end - node_index;
-            let node_items =
-                read_http_node_items(client, min_req_size, index_begin, node_index, length).await?;
-            // search through child nodes
-            for pos in node_index..end {
-                let node_pos = pos - node_index;
-                let node_item = &node_items[node_pos];
-                if !item.intersects(&node_item) {
-                    continue;
-                }
-                if is_leaf_node {
-                    results.push(SearchResultItem {
-                        offset: node_item.offset as usize,
-                        index: pos - leaf_nodes_offset,
-                    });
-                } else {
-                    queue.push(Reverse((node_item.offset as usize, level - 1)));
-                }
+        let mut results = Vec::new();
+        while let Some((node_index, end, level)) = search.next_node() {
+            if end <= self.node_items.len() {
+                let node_items = &self.node_items[node_index..end];
+                search.visit(node_index, level, node_items, &mut results);
+            } else {
+                let node_items = read_http_node_items(
+                    client,
+                    min_req_size,
+                    index_begin,
+                    node_index,
+                    end - node_index,
+                )
+                .await?;
+                search.check(node_index, level, &node_items)?;
+                search.visit(node_index, level, &node_items, &mut results);
             }
         }
         Ok(results)
@@ -582,35 +1243,48 @@ impl PackedRTree {
         self.num_nodes * size_of::<NodeItem>()
     }
 
-    pub fn index_size(num_items: usize, node_size: u16) -> usize {
-        assert!(node_size >= 2, "Node size must be at least 2");
-        assert!(num_items > 0, "Cannot create empty tree");
-        let node_size_min = cmp::min(cmp::max(node_size, 2), 65535) as usize;
-        // limit so that resulting size in bytes can be represented by uint64_t
-        // assert!(
-        //     num_items <= 1 << 56,
-        //     "Number of items must be less than 2^56"
-        // );
+    pub(crate) fn node_items(&self) -> &[NodeItem] {
+        &self.node_items
+    }
+
+    pub(crate) fn level_bounds(&self) -> &[(usize, usize)] {
+        &self.level_bounds
+    }
+
+    pub(crate) fn node_size(&self) -> u16 {
+        self.node_size
+    }
+
+    pub fn index_size(num_items: usize, node_size: u16) -> Result<usize> {
+        check_index_params(num_items, node_size)?;
+        let node_size = node_size as usize;
         let mut n = num_items;
         let mut num_nodes = n;
         loop {
-            n = (n + node_size_min - 1) / node_size_min;
+            n = (n + node_size - 1) / node_size;
             num_nodes += n;
             if n == 1 {
                 break;
             }
         }
-        num_nodes * size_of::<NodeItem>()
+        num_nodes
+            .checked_mul(size_of::<NodeItem>())
+            .ok_or(Error::IndexTooLarge(num_items))
     }
 
     pub fn stream_write(&self, out: &mut dyn Write) -> std::io::Result<()> {
-        let buf: &[u8] = unsafe {
-            std::slice::from_raw_parts(
-                self.node_items.as_ptr() as *const u8,
-                self.node_items.len() * size_of::<NodeItem>(),
-            )
-        };
-        out.write_all(buf)
+        let mut buf = vec![0; NODE_CHUNK_SIZE * size_of::<NodeItem>()];
+        for nodes in self.node_items.chunks(NODE_CHUNK_SIZE) {
+            let chunk = &mut buf[..nodes.len() * size_of::<NodeItem>()];
+            for (node, raw) in nodes
+                .iter()
+                .zip(chunk.chunks_exact_mut(size_of::<NodeItem>()))
+            {
+                node.write_bytes(raw);
+            }
+            out.write_all(chunk)?;
+        }
+        Ok(())
     }
 
     pub fn extent(&self) -> NodeItem {
@@ -624,6 +1298,9 @@ mod inspect {
 
     impl PackedRTree {
         pub fn process_index<P: FeatureProcessor>(&self, processor: &mut P) -> Result<()> {
+            if !self.is_loaded() {
+                return Err(Error::IndexNotLoaded);
+            }
             processor.dataset_begin(Some("PackedRTree"))?;
             let mut fid = 0;
             for (levelno, level) in self.level_bounds.iter().rev().enumerate() {
@@ -651,11 +1328,282 @@ mod inspect {
                     fid += 1;
                 }
             }
-            processor.dataset_end()
+            processor.dataset_end()?;
+            Ok(())
         }
     }
 }
 
+mod stats {
+    use super::*;
+    use std::fmt;
+
+    /// Index structure and quality metrics
+    #[derive(Clone, PartialEq, Debug)]
+    pub struct IndexStats {
+        pub num_items: usize,
+        pub node_size: u16,
+        /// Number of levels including the leaf level
+        pub num_levels: usize,
+        /// Number of nodes per level, from root to leaves
+        pub nodes_per_level: Vec<usize>,
+        /// Mean overlap of internal nodes, as summed pairwise intersection area of
+        /// the children relative to the node area
+        pub mean_overlap: f64,
+        /// Maximal overlap of internal nodes
+        pub max_overlap: f64,
+        /// Mean fraction of internal node area not covered by children
+        pub dead_space: f64,
+        /// Curve used for `leaves_out_of_order`
+        pub curve: SpaceFillingCurve,
+        /// Percentage of consecutive leaves not in descending order along `curve`
+        pub leaves_out_of_order: f64,
+    }
+
+    impl PackedRTree {
+        /// Calculate statistics of loaded index, with leaf order measured along the
+        /// 16 bit Hilbert curve used by default
+        pub fn stats(&self) -> Result<IndexStats> {
+            self.stats_for_curve(SpaceFillingCurve::Hilbert16)
+        }
+
+        /// Calculate statistics of loaded index built with items sorted along `curve`
+        pub fn stats_for_curve(&self, curve: SpaceFillingCurve) -> Result<IndexStats> {
+            if !self.is_loaded() {
+                return Err(Error::IndexNotLoaded);
+            }
+            let mut overlap_sum = 0.0;
+            let mut max_overlap: f64 = 0.0;
+            let mut dead_space_sum = 0.0;
+            let mut num_measured = 0;
+            for level in 1..self.level_bounds.len() {
+                let (start, end) = self.level_bounds[level];
+                for node_index in start..end {
+                    let node = &self.node_items[node_index];
+                    let area = node.area();
+                    if area <= 0.0 {
+                        continue;
+                    }
+                    let children = &self.node_items[self.child_nodes(node_index, level)];
+                    let mut children_area = 0.0;
+                    let mut overlap_area = 0.0;
+                    for (i, child) in children.iter().enumerate() {
+                        children_area += child.area();
+                        for other in &children[i + 1..] {
+                            if let Some(intersection) = child.intersection(other) {
+                                overlap_area += intersection.area();
+                            }
+                        }
+                    }
+                    let overlap = overlap_area / area;
+                    overlap_sum += overlap;
+                    max_overlap = max_overlap.max(overlap);
+                    // covered area estimated by inclusion-exclusion of child pairs
+                    let covered = (children_area - overlap_area).max(0.0).min(area);
+                    dead_space_sum += 1.0 - covered / area;
+                    num_measured += 1;
+                }
+            }
+
+            let (leaves_start, leaves_end) = self.level_bounds[0];
+            let leaves = &self.node_items[leaves_start..leaves_end];
+            let extent = &self.node_items[0];
+            let curve_values: Vec<u64> = leaves
+                .iter()
+                .map(|leaf| curve.index(leaf, extent))
+                .collect();
+            let out_of_order = curve_values.windows(2).filter(|w| w[0] < w[1]).count();
+
+            let mean = |sum: f64, count: usize| if count > 0 { sum / count as f64 } else { 0.0 };
+            Ok(IndexStats {
+                num_items: self.num_items,
+                node_size: self.node_size,
+                num_levels: self.level_bounds.len(),
+                nodes_per_level: self
+                    .level_bounds
+                    .iter()
+                    .rev()
+                    .map(|(start, end)| end - start)
+                    .collect(),
+                mean_overlap: mean(overlap_sum, num_measured),
+                max_overlap,
+                dead_space: mean(dead_space_sum, num_measured),
+                curve,
+                leaves_out_of_order: 100.0 * mean(out_of_order as f64, leaves.len() - 1),
+            })
+        }
+    }
+
+    impl fmt::Display for IndexStats {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            writeln!(f, "Items: {}", self.num_items)?;
+            writeln!(f, "Node size: {}", self.node_size)?;
+            writeln!(f, "Levels: {}", self.num_levels)?;
+            for (level, num_nodes) in self.nodes_per_level.iter().enumerate() {
+                writeln!(f, "  Level {}: {} nodes", level, num_nodes)?;
+            }
+            writeln!(
+                f,
+                "Node overlap: {:.4} mean, {:.4} max",
+                self.mean_overlap, self.max_overlap
+            )?;
+            writeln!(f, "Dead space: {:.1}%", 100.0 * self.dead_space)?;
+            writeln!(
+                f,
+                "Leaves out of {:?} order: {:.1}%",
+                self.curve, self.leaves_out_of_order
+            )
+        }
+    }
+}
+
+pub use stats::IndexStats;
+
+#[cfg(feature = "rayon")]
+mod parallel {
+    use super::*;
+    use rayon::prelude::*;
+
+    /// Parallel version of `hilbert_sort` with identical result.
+    /// Hilbert values are calculated once per item instead of in every comparison.
+    pub fn par_hilbert_sort(items: &mut Vec<NodeItem>) {
+        par_sort_by_curve(items, SpaceFillingCurve::Hilbert16)
+    }
+
+    /// Parallel version of `sort_by_curve` with identical result
+    pub fn par_sort_by_curve(items: &mut Vec<NodeItem>, curve: SpaceFillingCurve) {
+        let extent = items
+            .par_iter()
+            .fold(
+                || NodeItem::create(0),
+                |mut a, b| {
+                    a.expand(b);
+                    a
+                },
+            )
+            .reduce(
+                || NodeItem::create(0),
+                |mut a, b| {
+                    a.expand(&b);
+                    a
+                },
+            );
+        let mut keyed: Vec<(u64, NodeItem)> = items
+            .par_iter()
+            .map(|item| (curve.index(item, &extent), item.clone()))
+            .collect();
+        // stable sort, descending like `hilbert_sort`
+        keyed.par_sort_by(|a, b| b.0.cmp(&a.0));
+        *items = keyed.into_par_iter().map(|(_, item)| item).collect();
+    }
+
+    impl PackedRTree {
+        /// Parallel version of `build`, generating the nodes of each level in parallel
+        pub fn par_build(
+            nodes: &Vec<NodeItem>,
+            extent: &NodeItem,
+            node_size: u16,
+        ) -> Result<PackedRTree> {
+            let mut tree = PackedRTree {
+                extent: extent.clone(),
+                node_items: Vec::new(),
+                num_items: nodes.len(),
+                num_nodes: 0,
+                node_size: 0,
+                level_bounds: Vec::new(),
+            };
+            tree.init(node_size)?;
+            let leaf_nodes_offset = tree.num_nodes - tree.num_items;
+            tree.node_items[leaf_nodes_offset..]
+                .par_iter_mut()
+                .zip(nodes.par_iter())
+                .for_each(|(leaf, node)| *leaf = node.clone());
+            tree.par_generate_nodes();
+            Ok(tree)
+        }
+
+        fn par_generate_nodes(&mut self) {
+            let node_size = self.node_size as usize;
+            for i in 0..self.level_bounds.len() - 1 {
+                let (start, end) = self.level_bounds[i];
+                let (parent_start, parent_end) = self.level_bounds[i + 1];
+                // parent level is stored before its child level
+                let (upper, lower) = self.node_items.split_at_mut(start);
+                upper[parent_start..parent_end]
+                    .par_iter_mut()
+                    .zip(lower[..end - start].par_chunks(node_size))
+                    .enumerate()
+                    .for_each(|(j, (parent, children))| {
+                        let mut node = NodeItem::create((start + j * node_size) as u64);
+                        for child in children {
+                            node.expand(child);
+                        }
+                        *parent = node;
+                    });
+            }
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+pub use parallel::{par_hilbert_sort, par_sort_by_curve};
+
+#[test]
+fn node_item_operations() {
+    let a = NodeItem::new(0.0, 0.0, 2.0, 2.0);
+    let b = NodeItem::from([1.0, 1.0, 3.0, 4.0]);
+    assert_eq!(a.area(), 4.0);
+    assert_eq!(a.union(&b).to_array(), [0.0, 0.0, 3.0, 4.0]);
+    assert_eq!(a.intersection(&b), Some(NodeItem::new(1.0, 1.0, 2.0, 2.0)));
+    assert_eq!(a.intersection(&NodeItem::new(5.0, 5.0, 6.0, 6.0)), None);
+    assert!(a.contains(&NodeItem::new(0.5, 0.5, 1.0, 1.0)));
+    assert!(!a.contains(&b));
+
+    let mut node = NodeItem::create(0);
+    assert!(node.is_empty());
+    assert_eq!(node.area(), 0.0);
+    assert!(!a.contains(&node));
+    node.expand(&a);
+    node.expand_xy(-1.0, 5.0);
+    node.set_offset(40);
+    assert_eq!(<[f64; 4]>::from(node.clone()), [-1.0, 0.0, 2.0, 5.0]);
+    assert_eq!(node.offset(), 40);
+    assert_eq!((node.min_x(), node.max_y()), (-1.0, 5.0));
+}
+
+#[test]
+fn node_item_little_endian() -> Result<()> {
+    let mut node = NodeItem::new(1.0, 2.0, 3.0, 4.0);
+    node.set_offset(5);
+    let mut raw = [0; 40];
+    node.write_bytes(&mut raw);
+    assert_eq!(raw[..8], 1.0f64.to_le_bytes());
+    assert_eq!(raw[24..32], 4.0f64.to_le_bytes());
+    assert_eq!(raw[32..], 5u64.to_le_bytes());
+    assert_eq!(NodeItem::from_bytes(&raw), node);
+
+    let nodes: Vec<NodeItem> = (0..5000)
+        .map(|i| {
+            let mut node = NodeItem::new(i as f64, 0.0, i as f64 + 1.0, 1.0);
+            node.set_offset(i * 40);
+            node
+        })
+        .collect();
+    let extent = calc_extent(&nodes);
+    let tree = PackedRTree::build(&nodes, &extent, PackedRTree::DEFAULT_NODE_SIZE)?;
+    let mut data = Vec::new();
+    tree.stream_write(&mut data)?;
+    assert_eq!(data.len(), tree.size());
+    // root node
+    assert_eq!(
+        NodeItem::from_bytes(&data[..40]).to_array(),
+        extent.to_array()
+    );
+    let tree2 = PackedRTree::from_buf(&mut &data[..], 5000, PackedRTree::DEFAULT_NODE_SIZE)?;
+    assert_eq!(tree2.node_items, tree.node_items);
+    Ok(())
+}
+
 #[test]
 fn tree_2items() -> Result<()> {
     let mut nodes = Vec::new();
@@ -667,7 +1615,7 @@ fn tree_2items() -> Result<()> {
     assert!(nodes[1].intersects(&NodeItem::new(2.0, 2.0, 3.0, 3.0)));
     hilbert_sort(&mut nodes);
     let mut offset = 0;
-    for mut node in &mut nodes {
+    for node in &mut nodes {
         node.offset = offset as u64;
         offset += size_of::<NodeItem>();
     }
@@ -705,7 +1653,7 @@ fn tree_19items_roundtrip_stream_search() -> Result<()> {
     let extent = calc_extent(&nodes);
     hilbert_sort(&mut nodes);
     let mut offset = 0;
-    for mut node in &mut nodes {
+    for node in &mut nodes {
         node.offset = offset as u64;
         offset += size_of::<NodeItem>();
     }
@@ -746,6 +1694,26 @@ fn tree_19items_roundtrip_stream_search() -> Result<()> {
     for i in 0..list.len() {
         assert!(nodes[list[i].index].intersects(&NodeItem::new(102.0, 102.0, 103.0, 103.0)));
     }
+
+    // lazy searches return the same hits
+    let offsets =
+        |list: &[SearchResultItem]| list.iter().map(|item| item.offset).collect::<Vec<_>>();
+    let reader = std::io::Cursor::new(&tree_data);
+    let lazy_list = PackedRTree::stream_search_iter(
+        reader,
+        nodes.len(),
+        PackedRTree::DEFAULT_NODE_SIZE,
+        102.0,
+        102.0,
+        103.0,
+        103.0,
+    )?
+    .collect::<Result<Vec<_>>>()?;
+    assert_eq!(offsets(&lazy_list), offsets(&list));
+    let lazy_list = tree2
+        .search_iter(102.0, 102.0, 103.0, 103.0)?
+        .collect::<Vec<_>>();
+    assert_eq!(offsets(&lazy_list), offsets(&list));
     Ok(())
 }
 
@@ -793,6 +1761,262 @@ fn tree_100_000_items_in_denmark() -> Result<()> {
         assert!(nodes[list2[i].index]
             .intersects(&NodeItem::new(690407.0, 6063692.0, 811682.0, 6176467.0)));
     }
+
+    // depth-first search returns the same hits and keeps only the current path
+    let mut search = IndexSearch::depth_first(
+        0,
+        nodes.len(),
+        PackedRTree::DEFAULT_NODE_SIZE,
+        690407.0,
+        6063692.0,
+        811682.0,
+        6176467.0,
+    )?;
+    let mut list3 = Vec::new();
+    let mut max_queue_len = 0;
+    while let Some(item) = search.next_item(&mut reader)? {
+        max_queue_len = cmp::max(max_queue_len, search.search.queue.len());
+        list3.push(item.index);
+    }
+    let list2: Vec<_> = list2.iter().map(|item| item.index).collect();
+    assert_eq!(list3, list2);
+    let levels = search.search.level_bounds.len();
+    assert!(max_queue_len <= levels * PackedRTree::DEFAULT_NODE_SIZE as usize);
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn tree_parallel_build() -> Result<()> {
+    use rand::distributions::{Distribution, Uniform};
+
+    let unif = Uniform::from(0..1000);
+    let mut rng = rand::thread_rng();
+    let mut nodes = Vec::new();
+    for i in 0..20000 {
+        // many duplicates to check sort stability
+        let x = unif.sample(&mut rng) as f64;
+        let y = unif.sample(&mut rng) as f64;
+        let mut node = NodeItem::new(x, y, x + 1.0, y + 1.0);
+        node.set_offset(i);
+        nodes.push(node);
+    }
+    let mut par_nodes = nodes.clone();
+    hilbert_sort(&mut nodes);
+    par_hilbert_sort(&mut par_nodes);
+    assert_eq!(par_nodes, nodes);
+    for curve in &[SpaceFillingCurve::Hilbert32, SpaceFillingCurve::ZOrder] {
+        let mut sorted = nodes.clone();
+        sort_by_curve(&mut sorted, *curve);
+        let mut par_sorted = nodes.clone();
+        par_sort_by_curve(&mut par_sorted, *curve);
+        assert_eq!(par_sorted, sorted);
+    }
+
+    let extent = calc_extent(&nodes);
+    for node_size in &[2, 5, PackedRTree::DEFAULT_NODE_SIZE] {
+        let tree = PackedRTree::build(&nodes, &extent, *node_size)?;
+        let par_tree = PackedRTree::par_build(&nodes, &extent, *node_size)?;
+        assert_eq!(par_tree.node_items, tree.node_items);
+    }
+    Ok(())
+}
+
+#[test]
+fn space_filling_curves() {
+    // consecutive cells of a 64x64 grid are adjacent and distinct
+    let adjacent = |curve: fn(u32, u32) -> u64, offset: u32| {
+        let mut cells = Vec::new();
+        for x in 0..64 {
+            for y in 0..64 {
+                cells.push((curve(offset + x, offset + y), x as i64, y as i64));
+            }
+        }
+        cells.sort();
+        cells
+            .windows(2)
+            .all(|w| w[0].0 != w[1].0 && (w[0].1 - w[1].1).abs() + (w[0].2 - w[1].2).abs() == 1)
+    };
+    assert!(adjacent(|x, y| hilbert(x, y) as u64, 0));
+    assert!(adjacent(hilbert64, 0));
+    assert!(adjacent(hilbert64, 0x8000_0000));
+    // 32 bit curve refines the 16 bit curve
+    for (x, y) in &[(0, 0), (1, 2), (65535, 0), (1234, 54321), (65535, 65535)] {
+        assert_eq!(hilbert64(x << 16, y << 16) >> 32, hilbert(*x, *y) as u64);
+    }
+    assert_eq!(interleave(0b11), 0b101);
+
+    let mut nodes = Vec::new();
+    for i in 0..1000 {
+        let x = (i % 37) as f64;
+        let y = (i % 101) as f64;
+        let mut node = NodeItem::new(x, y, x + 1.0, y + 1.0);
+        node.set_offset(i);
+        nodes.push(node);
+    }
+    let mut sorted = nodes.clone();
+    hilbert_sort(&mut sorted);
+    let mut curve_sorted = nodes.clone();
+    sort_by_curve(&mut curve_sorted, SpaceFillingCurve::Hilbert16);
+    assert_eq!(curve_sorted, sorted);
+
+    let extent = calc_extent(&nodes);
+    for curve in &[SpaceFillingCurve::Hilbert32, SpaceFillingCurve::ZOrder] {
+        let mut sorted = nodes.clone();
+        sort_by_curve(&mut sorted, *curve);
+        assert!(sorted
+            .windows(2)
+            .all(|w| curve.index(&w[0], &extent) >= curve.index(&w[1], &extent)));
+    }
+}
+
+#[test]
+fn tree_join() -> Result<()> {
+    let grid = |n: usize, size: f64, offset: f64| {
+        let mut nodes = Vec::new();
+        for i in 0..n * n {
+            let x = (i % n) as f64 * size + offset;
+            let y = (i / n) as f64 * size + offset;
+            nodes.push(NodeItem::new(x, y, x + size * 0.8, y + size * 0.8));
+        }
+        hilbert_sort(&mut nodes);
+        for (i, node) in nodes.iter_mut().enumerate() {
+            node.offset = i as u64 * 10;
+        }
+        nodes
+    };
+    let points: Vec<NodeItem> = grid(40, 1.0, 0.3)
+        .into_iter()
+        .map(|n| NodeItem {
+            max_x: n.min_x,
+            max_y: n.min_y,
+            ..n
+        })
+        .collect();
+    let polygons = grid(7, 5.0, 0.0);
+    let tree_points = PackedRTree::build(&points, &calc_extent(&points), 4)?;
+    let tree_polygons = PackedRTree::build(
+        &polygons,
+        &calc_extent(&polygons),
+        PackedRTree::DEFAULT_NODE_SIZE,
+    )?;
+
+    let mut expected = Vec::new();
+    for a in &polygons {
+        for b in &points {
+            if a.intersects(b) {
+                expected.push((a.offset as usize, b.offset as usize));
+            }
+        }
+    }
+    expected.sort();
+    assert!(!expected.is_empty());
+
+    let pairs = tree_polygons.join(&tree_points)?;
+    let offsets: Vec<_> = pairs.iter().map(|(a, b)| (a.offset, b.offset)).collect();
+    assert_eq!(offsets, expected);
+    for (a, b) in &pairs {
+        assert_eq!(polygons[a.index], a.bbox);
+        assert_eq!(points[b.index], b.bbox);
+        assert_eq!(Some(&a.bbox), tree_polygons.leaf_node(a.index));
+    }
+
+    let pairs = tree_points.join(&tree_polygons)?;
+    assert_eq!(pairs.len(), expected.len());
+    Ok(())
+}
+
+#[test]
+fn tree_stats() -> Result<()> {
+    let mut nodes = vec![
+        NodeItem::new(0.0, 0.0, 1.0, 1.0),
+        NodeItem::new(2.0, 0.0, 3.0, 1.0),
+        NodeItem::new(0.0, 2.0, 1.0, 3.0),
+        NodeItem::new(2.0, 2.0, 3.0, 3.0),
+    ];
+    hilbert_sort(&mut nodes);
+    let extent = calc_extent(&nodes);
+    let stats = PackedRTree::build(&nodes, &extent, 2)?.stats()?;
+    assert_eq!(stats.num_levels, 3);
+    assert_eq!(stats.nodes_per_level, vec![1, 2, 4]);
+    assert_eq!(stats.mean_overlap, 0.0);
+    // adjacent squares are grouped, each parent covers 2 of 3 area units
+    assert!((stats.dead_space - 1.0 / 3.0).abs() < 1e-9);
+    assert_eq!(stats.leaves_out_of_order, 0.0);
+
+    nodes.reverse();
+    let stats = PackedRTree::build(&nodes, &extent, 2)?.stats()?;
+    assert_eq!(stats.leaves_out_of_order, 100.0);
+
+    sort_by_curve(&mut nodes, SpaceFillingCurve::ZOrder);
+    let tree = PackedRTree::build(&nodes, &extent, 2)?;
+    let stats = tree.stats_for_curve(SpaceFillingCurve::ZOrder)?;
+    assert_eq!(stats.curve, SpaceFillingCurve::ZOrder);
+    assert_eq!(stats.leaves_out_of_order, 0.0);
+    assert!(tree.stats()?.leaves_out_of_order > 0.0);
+
+    let nodes = vec![
+        NodeItem::new(0.0, 0.0, 1.0, 1.0),
+        NodeItem::new(0.0, 0.0, 1.0, 1.0),
+    ];
+    let stats = PackedRTree::build(&nodes, &calc_extent(&nodes), 2)?.stats()?;
+    assert_eq!(stats.nodes_per_level, vec![1, 2]);
+    assert_eq!(stats.max_overlap, 1.0);
+    assert_eq!(stats.dead_space, 0.0);
+    assert!(stats.to_string().contains("Levels: 2"));
+    Ok(())
+}
+
+#[test]
+fn tree_errors() -> Result<()> {
+    let nodes = vec![NodeItem::new(0.0, 0.0, 1.0, 1.0)];
+    let extent = calc_extent(&nodes);
+    assert!(matches!(
+        PackedRTree::build(&nodes, &extent, 1),
+        Err(Error::IndexNodeSize(1))
+    ));
+    assert!(matches!(
+        PackedRTree::build(&Vec::new(), &extent, 16),
+        Err(Error::IndexEmpty)
+    ));
+    assert!(matches!(
+        PackedRTree::index_size(0, 16),
+        Err(Error::IndexEmpty)
+    ));
+    assert!(matches!(
+        PackedRTree::index_size(usize::MAX / 2, 16),
+        Err(Error::IndexTooLarge(_))
+    ));
+
+    let mut nodes = Vec::new();
+    for i in 0..20 {
+        let x = i as f64;
+        nodes.push(NodeItem::new(x, x, x + 1.0, x + 1.0));
+    }
+    let tree = PackedRTree::build(&nodes, &calc_extent(&nodes), 4)?;
+    let mut data = Vec::new();
+    tree.stream_write(&mut data)?;
+    // root references a child beyond the second level
+    LittleEndian::write_u64(&mut data[32..40], 100);
+    match PackedRTree::from_buf(&mut &data[..], 20, 4) {
+        Err(Error::IndexChildOffset {
+            node,
+            offset,
+            min,
+            max,
+        }) => assert_eq!((node, offset, min, max), (0, 100, 1, 3)),
+        _ => panic!("child offset error expected"),
+    }
+    let result = PackedRTree::stream_search(
+        &mut std::io::Cursor::new(&data),
+        20,
+        4,
+        0.0,
+        0.0,
+        20.0,
+        20.0,
+    );
+    assert!(matches!(result, Err(Error::IndexChildOffset { .. })));
     Ok(())
 }
 
@@ -807,7 +2031,7 @@ fn tree_processing() -> Result<()> {
     nodes.push(NodeItem::new(2.0, 2.0, 3.0, 3.0));
     let extent = calc_extent(&nodes);
     let mut offset = 0;
-    for mut node in &mut nodes {
+    for node in &mut nodes {
         node.offset = offset as u64;
         offset += size_of::<NodeItem>();
     }
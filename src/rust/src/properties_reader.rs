@@ -1,5 +1,6 @@
 use crate::feature_generated::flat_geobuf::*;
 use crate::header_generated::flat_geobuf::*;
+use crate::packed_r_tree::NodeItem;
 use byteorder::{ByteOrder, LittleEndian};
 use geozero::error::{GeozeroError, Result};
 use geozero::{ColumnValue, FeatureProcessor, PropertyProcessor};
@@ -9,22 +10,30 @@ use std::str;
 
 /// Access to current feature
 pub struct FgbFeature {
+    /// Size prefixed header buffer, verified by the readers
     pub(crate) header_buf: Vec<u8>,
+    /// Size prefixed feature buffer, verified by the readers unless disabled with `set_verify`
     pub(crate) feature_buf: Vec<u8>,
 }
 
 impl FgbFeature {
-    pub(crate) fn header(&self) -> Header {
-        get_root_as_header(&self.header_buf[..])
+    pub(crate) fn header(&self) -> Header<'_> {
+        // Safety: header is verified when opening the dataset
+        unsafe { size_prefixed_root_as_header_unchecked(&self.header_buf) }
     }
     // Flatbuffers feature access
-    pub fn fbs_feature(&self) -> Feature {
-        get_root_as_feature(&self.feature_buf[..])
+    pub fn fbs_feature(&self) -> Feature<'_> {
+        // Safety: feature is verified when reading it, unless disabled by the unsafe `set_verify`
+        unsafe { size_prefixed_root_as_feature_unchecked(&self.feature_buf) }
     }
     // Flatbuffers geometry access
-    pub fn geometry(&self) -> Option<Geometry> {
+    pub fn geometry(&self) -> Option<Geometry<'_>> {
         self.fbs_feature().geometry()
     }
+    /// Bounding box computed from geometry
+    pub fn bbox(&self) -> Result<NodeItem> {
+        Ok(self.geometry().ok_or(GeozeroError::GeometryFormat)?.bbox())
+    }
     /// Process current feature
     pub fn process<R: FeatureProcessor>(&self, reader: &mut R, idx: u64) -> Result<()> {
         reader.feature_begin(idx)?;
@@ -48,6 +57,7 @@ impl FgbFeature {
             .ok_or(GeozeroError::GeometryFormat)?;
         let mut finish = false;
         if let Some(properties) = self.fbs_feature().properties() {
+            let properties = properties.bytes();
             let mut offset = 0;
             while offset < properties.len() - 1 && !finish {
                 // NOTE: it should be offset < properties.len(), but there is data with a
@@ -221,6 +231,12 @@ impl FgbFeature {
                         )?;
                         offset += len;
                     }
+                    ColumnType(type_) => {
+                        return Err(GeozeroError::Property(format!(
+                            "Unknown column type {}",
+                            type_
+                        )));
+                    }
                 }
             }
         }
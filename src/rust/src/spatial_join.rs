@@ -0,0 +1,162 @@
+//! Spatial join between two indexed datasets.
+
+use crate::error::Result;
+use crate::file_reader::FgbReader;
+use crate::owned_geometry::OwnedGeometry;
+use crate::packed_r_tree::SearchResultItem;
+use crate::properties_reader::FgbFeature;
+use geozero::error::{GeozeroError, Result as GeozeroResult};
+use std::collections::HashMap;
+use std::io::SeekFrom;
+
+/// Maximal size of the feature buffers of the first dataset kept by `FgbReader::join_refined`
+pub const JOIN_BUFFER_SIZE: usize = 64 * 1_048_576;
+
+impl FgbReader<'_> {
+    /// Spatial join with `other` using the indexes of both datasets.
+    /// Returns candidate pairs of features with intersecting bounding boxes, ordered by
+    /// feature offset. The `index` of the result items is the feature number.
+    ///
+    /// Both complete indexes are loaded into memory (40 bytes per feature and dataset, plus
+    /// internal nodes), and all candidate pairs are collected before returning.
+    pub fn join(
+        &mut self,
+        other: &mut FgbReader,
+    ) -> Result<Vec<(SearchResultItem, SearchResultItem)>> {
+        let index = self.read_index()?;
+        let other_index = other.read_index()?;
+        index.join(&other_index)
+    }
+
+    /// Spatial join with `other`, keeping the candidate pairs matching `predicate`.
+    /// Features of `self` are passed as first argument, see `point_in_polygon`.
+    ///
+    /// Candidates are refined in chunks. The features of `self` of a chunk are read and kept
+    /// in memory up to `JOIN_BUFFER_SIZE` bytes, then the features of `other` paired with them
+    /// are read once each in offset order. Besides the memory used by `join`, memory usage is
+    /// bounded by the chunk buffers.
+    pub fn join_refined<P>(
+        &mut self,
+        other: &mut FgbReader,
+        predicate: P,
+    ) -> Result<Vec<(SearchResultItem, SearchResultItem)>>
+    where
+        P: FnMut(&FgbFeature, &FgbFeature) -> GeozeroResult<bool>,
+    {
+        self.join_refined_buffered(other, predicate, JOIN_BUFFER_SIZE)
+    }
+
+    fn join_refined_buffered<P>(
+        &mut self,
+        other: &mut FgbReader,
+        mut predicate: P,
+        buffer_size: usize,
+    ) -> Result<Vec<(SearchResultItem, SearchResultItem)>>
+    where
+        P: FnMut(&FgbFeature, &FgbFeature) -> GeozeroResult<bool>,
+    {
+        let candidates = self.join(other)?;
+        let pos = self.reader_position()?;
+        let other_pos = other.reader_position()?;
+        let mut matches = vec![false; candidates.len()];
+        let mut feature = FgbFeature {
+            header_buf: self.cur_feature().header_buf.clone(),
+            feature_buf: Vec::new(),
+        };
+        let mut start = 0;
+        while start < candidates.len() {
+            // feature buffers of `self` by offset.
+            // Candidates are ordered by offset in `self`, so a feature is never split across chunks.
+            let mut buffers: HashMap<usize, Vec<u8>> = HashMap::new();
+            let mut size = 0;
+            let mut end = start;
+            while end < candidates.len() {
+                let item = &candidates[end].0;
+                if !buffers.contains_key(&item.offset) {
+                    if size >= buffer_size {
+                        break;
+                    }
+                    let buffer = &self.read_feature_at(item.index, item.offset)?.feature_buf;
+                    size += buffer.len();
+                    buffers.insert(item.offset, buffer.clone());
+                }
+                end += 1;
+            }
+            let mut chunk: Vec<usize> = (start..end).collect();
+            chunk.sort_by_key(|&i| candidates[i].1.offset);
+            let mut current = None;
+            for i in chunk {
+                let (item, other_item) = &candidates[i];
+                if current != Some(other_item.offset) {
+                    other.read_feature_at(other_item.index, other_item.offset)?;
+                    current = Some(other_item.offset);
+                }
+                let buffer = buffers
+                    .get_mut(&item.offset)
+                    .expect("feature buffer of chunk");
+                std::mem::swap(&mut feature.feature_buf, buffer);
+                let matched = predicate(&feature, other.cur_feature());
+                std::mem::swap(&mut feature.feature_buf, buffer);
+                matches[i] = matched?;
+            }
+            start = end;
+        }
+        self.seek_reader(SeekFrom::Start(pos))?;
+        other.seek_reader(SeekFrom::Start(other_pos))?;
+        Ok(candidates
+            .into_iter()
+            .zip(matches)
+            .filter_map(|(pair, matched)| if matched { Some(pair) } else { None })
+            .collect())
+    }
+}
+
+fn owned_geometry(feature: &FgbFeature) -> GeozeroResult<OwnedGeometry> {
+    let geometry = feature.geometry().ok_or(GeozeroError::GeometryFormat)?;
+    geometry.to_owned_geometry(feature.header().geometry_type())
+}
+
+/// Join predicate matching point features within polygon features
+pub fn point_in_polygon(polygon: &FgbFeature, point: &FgbFeature) -> GeozeroResult<bool> {
+    let polygon = owned_geometry(polygon)?;
+    let matches = match owned_geometry(point)? {
+        OwnedGeometry::Point(coord) => polygon.contains_point(coord.x, coord.y),
+        OwnedGeometry::MultiPoint(coords) => coords
+            .iter()
+            .any(|coord| polygon.contains_point(coord.x, coord.y)),
+        _ => false,
+    };
+    Ok(matches)
+}
+
+#[test]
+fn join_refined_chunks() -> Result<()> {
+    use std::fs::File;
+    use std::io::BufReader;
+
+    let open = || BufReader::new(File::open("../../test/data/countries.fgb").unwrap());
+    let (mut file, mut file2) = (open(), open());
+    let mut fgb = FgbReader::open(&mut file)?;
+    let mut fgb2 = FgbReader::open(&mut file2)?;
+    let mut calls = 0;
+    let same_name = |a: &FgbFeature, b: &FgbFeature| -> GeozeroResult<bool> {
+        Ok(a.properties()?["name"] == b.properties()?["name"])
+    };
+    let expected = fgb.join_refined(&mut fgb2, same_name)?;
+    // one feature of `self` per chunk
+    let pairs = fgb.join_refined_buffered(
+        &mut fgb2,
+        |a, b| {
+            calls += 1;
+            same_name(a, b)
+        },
+        1,
+    )?;
+    assert_eq!(calls, fgb.join(&mut fgb2)?.len());
+    assert_eq!(pairs.len(), 179);
+    let offsets = |pairs: &[(SearchResultItem, SearchResultItem)]| -> Vec<(usize, usize)> {
+        pairs.iter().map(|(a, b)| (a.offset, b.offset)).collect()
+    };
+    assert_eq!(offsets(&pairs), offsets(&expected));
+    Ok(())
+}
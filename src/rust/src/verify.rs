@@ -0,0 +1,299 @@
+//! Integrity verification of complete FlatGeobuf files.
+
+use crate::buffer_check::{verify_feature, verify_header};
+use crate::error::{Error, Result};
+use crate::packed_r_tree::{NodeItem, PackedRTree};
+use crate::version::Version;
+use crate::{HEADER_MAX_BUFFER_SIZE, MAGIC_BYTES};
+use byteorder::{ByteOrder, LittleEndian};
+use std::cmp;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::mem::size_of;
+
+/// Problem found by `verify`
+#[derive(Clone, PartialEq, Debug)]
+pub enum VerifyProblem {
+    /// File does not start with FlatGeobuf magic bytes
+    MagicBytes,
+    /// Unsupported major version. Verification stops after reporting it.
+    Version(u8),
+    /// Header size out of range
+    HeaderSize(usize),
+    /// Header buffer is invalid
+    Header(String),
+    /// File ends within section
+    Truncated { section: &'static str, offset: u64 },
+    /// Feature buffer is invalid
+    Feature {
+        feature: usize,
+        offset: u64,
+        message: String,
+    },
+    /// Number of features differs from header
+    FeaturesCount { header: u64, actual: u64 },
+    /// Internal node does not reference its first child
+    ChildOffset {
+        node: usize,
+        offset: u64,
+        expected: u64,
+    },
+    /// Internal node bounds do not contain child bounds
+    NodeBounds { node: usize, child: usize },
+    /// Leaf offset does not point to the start of a feature
+    LeafOffset { leaf: usize, offset: u64 },
+    /// Leaf offset is not greater than offset of previous leaf
+    LeafOrder { leaf: usize, offset: u64 },
+    /// Leaf bounds do not contain feature geometry bounds
+    LeafBounds { leaf: usize, feature: usize },
+}
+
+/// Result of `verify`
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct VerifyReport {
+    /// Number of features found in feature section
+    pub features_count: u64,
+    /// All problems found
+    pub problems: Vec<VerifyProblem>,
+}
+
+impl VerifyReport {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Verify header, index and all features of a FlatGeobuf file.
+/// Problems are collected in the report, errors are only returned for I/O failures.
+pub fn verify<R: Read + Seek>(reader: &mut R) -> Result<VerifyReport> {
+    let mut report = VerifyReport::default();
+    let problems = &mut report.problems;
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+    let header_base = (MAGIC_BYTES.len() + 4) as u64;
+    if file_len < header_base {
+        problems.push(VerifyProblem::Truncated {
+            section: "header",
+            offset: 0,
+        });
+        return Ok(report);
+    }
+
+    let mut magic_buf = [0; 8];
+    reader.read_exact(&mut magic_buf)?;
+    let version = match Version::from_magic_bytes(&magic_buf) {
+        Ok(version) => version,
+        Err(Error::UnsupportedVersion(version)) => {
+            problems.push(VerifyProblem::Version(version.major));
+            return Ok(report);
+        }
+        Err(_) => {
+            problems.push(VerifyProblem::MagicBytes);
+            return Ok(report);
+        }
+    };
+
+    let mut size_buf = [0; 4];
+    reader.read_exact(&mut size_buf)?;
+    let header_size = u32::from_le_bytes(size_buf) as usize;
+    if header_size > HEADER_MAX_BUFFER_SIZE || header_size < 8 {
+        problems.push(VerifyProblem::HeaderSize(header_size));
+        return Ok(report);
+    }
+    if header_base + header_size as u64 > file_len {
+        problems.push(VerifyProblem::Truncated {
+            section: "header",
+            offset: header_base,
+        });
+        return Ok(report);
+    }
+    let mut header_buf = vec![0; 4 + header_size];
+    header_buf[..4].copy_from_slice(&size_buf);
+    reader.read_exact(&mut header_buf[4..])?;
+    let header = match verify_header(&header_buf) {
+        Ok(header) => header,
+        Err(message) => {
+            problems.push(VerifyProblem::Header(message));
+            return Ok(report);
+        }
+    };
+    let features_count = header.features_count();
+    let node_size = header.index_node_size();
+
+    let index_base = header_base + header_size as u64;
+    let has_index = node_size > 0 && features_count > 0;
+    if node_size == 1 {
+        problems.push(VerifyProblem::Header(format!(
+            "Invalid index node size {}",
+            node_size
+        )));
+        return Ok(report);
+    }
+    // avoids huge allocations for corrupt feature counts
+    if has_index && features_count > (file_len - index_base) / size_of::<NodeItem>() as u64 {
+        problems.push(VerifyProblem::Truncated {
+            section: "index",
+            offset: index_base,
+        });
+        return Ok(report);
+    }
+    let index_size = if has_index {
+        version.index_size(features_count as usize, node_size)? as u64
+    } else {
+        0
+    };
+    let feature_base = index_base + index_size;
+    if feature_base > file_len {
+        problems.push(VerifyProblem::Truncated {
+            section: "index",
+            offset: index_base,
+        });
+        return Ok(report);
+    }
+
+    let features = verify_features(reader, feature_base, file_len, problems)?;
+    report.features_count = features.len() as u64;
+    if features_count != 0 && features_count != report.features_count {
+        report.problems.push(VerifyProblem::FeaturesCount {
+            header: features_count,
+            actual: report.features_count,
+        });
+    }
+
+    if has_index {
+        reader.seek(SeekFrom::Start(index_base))?;
+        let tree = PackedRTree::from_buf_unchecked(reader, features_count as usize, node_size)?;
+        // version 2 leaves contain feature numbers, resolved with the following offsets table
+        let feature_offsets = if version.has_feature_offsets() {
+            let mut table = vec![0; features_count as usize * 8];
+            reader.read_exact(&mut table)?;
+            let offsets: Vec<u64> = table
+                .chunks_exact(8)
+                .map(|chunk| LittleEndian::read_u64(chunk))
+                .collect();
+            Some(offsets)
+        } else {
+            None
+        };
+        verify_index(
+            &tree,
+            feature_offsets.as_deref(),
+            &features,
+            &mut report.problems,
+        );
+    }
+    Ok(report)
+}
+
+/// Verify all features and return their offsets and geometry bounds
+fn verify_features<R: Read + Seek>(
+    reader: &mut R,
+    feature_base: u64,
+    file_len: u64,
+    problems: &mut Vec<VerifyProblem>,
+) -> Result<Vec<(u64, Option<NodeItem>)>> {
+    reader.seek(SeekFrom::Start(feature_base))?;
+    let mut reader = BufReader::new(reader);
+    let mut features = Vec::new();
+    let mut feature_buf = Vec::new();
+    let mut pos = feature_base;
+    while pos < file_len {
+        let mut size_buf = [0; 4];
+        if pos + 4 > file_len {
+            problems.push(VerifyProblem::Truncated {
+                section: "features",
+                offset: pos,
+            });
+            break;
+        }
+        reader.read_exact(&mut size_buf)?;
+        let feature_size = u32::from_le_bytes(size_buf) as u64;
+        if pos + 4 + feature_size > file_len {
+            problems.push(VerifyProblem::Truncated {
+                section: "features",
+                offset: pos,
+            });
+            break;
+        }
+        feature_buf.resize(4 + feature_size as usize, 0);
+        feature_buf[..4].copy_from_slice(&size_buf);
+        reader.read_exact(&mut feature_buf[4..])?;
+        let offset = pos - feature_base;
+        let bbox = match verify_feature(&feature_buf) {
+            Ok(feature) => feature.geometry().map(|geometry| geometry.bbox()),
+            Err(message) => {
+                problems.push(VerifyProblem::Feature {
+                    feature: features.len(),
+                    offset,
+                    message,
+                });
+                None
+            }
+        };
+        features.push((offset, bbox));
+        pos += 4 + feature_size;
+    }
+    Ok(features)
+}
+
+fn verify_index(
+    tree: &PackedRTree,
+    feature_offsets: Option<&[u64]>,
+    features: &[(u64, Option<NodeItem>)],
+    problems: &mut Vec<VerifyProblem>,
+) {
+    let nodes = tree.node_items();
+    let level_bounds = tree.level_bounds();
+    let node_size = tree.node_size() as usize;
+    for level in 1..level_bounds.len() {
+        let (start, end) = level_bounds[level];
+        let (child_start, child_end) = level_bounds[level - 1];
+        for node in start..end {
+            let first_child = child_start + (node - start) * node_size;
+            if nodes[node].offset() != first_child as u64 {
+                problems.push(VerifyProblem::ChildOffset {
+                    node,
+                    offset: nodes[node].offset(),
+                    expected: first_child as u64,
+                });
+            }
+            for child in first_child..cmp::min(first_child + node_size, child_end) {
+                if !nodes[child].is_empty() && !nodes[node].contains(&nodes[child]) {
+                    problems.push(VerifyProblem::NodeBounds { node, child });
+                }
+            }
+        }
+    }
+
+    let (leaf_start, leaf_end) = level_bounds[0];
+    let mut previous = None;
+    for (leaf, node) in nodes[leaf_start..leaf_end].iter().enumerate() {
+        let offset = match feature_offsets {
+            Some(feature_offsets) => match feature_offsets.get(node.offset() as usize) {
+                Some(offset) => *offset,
+                None => {
+                    problems.push(VerifyProblem::LeafOffset {
+                        leaf,
+                        offset: node.offset(),
+                    });
+                    continue;
+                }
+            },
+            None => node.offset(),
+        };
+        // features of version 2 are not required to be stored in index order
+        if feature_offsets.is_none() && previous.map_or(false, |previous| offset <= previous) {
+            problems.push(VerifyProblem::LeafOrder { leaf, offset });
+        }
+        previous = Some(offset);
+        match features.binary_search_by_key(&offset, |(offset, _)| *offset) {
+            Ok(feature) => {
+                if let Some(bbox) = &features[feature].1 {
+                    if !bbox.is_empty() && !node.contains(bbox) {
+                        problems.push(VerifyProblem::LeafBounds { leaf, feature });
+                    }
+                }
+            }
+            Err(_) => problems.push(VerifyProblem::LeafOffset { leaf, offset }),
+        }
+    }
+}
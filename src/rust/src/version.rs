@@ -0,0 +1,214 @@
+//! Format versions and reading of version 2 files.
+//!
+//! Headers and features of version 2 files have the same layout as version 3. Leaf nodes
+//! of a version 2 index contain feature numbers instead of byte offsets, and the index is
+//! followed by a table with the byte offset (u64) of each feature.
+
+use crate::error::{Error, Result};
+use crate::packed_r_tree::dataset_index_size;
+use crate::{MAGIC_BYTES, VERSION};
+
+/// Format version from the magic bytes of a file
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Version {
+    pub major: u8,
+    /// Patch version, which does not change the layout
+    pub patch: u8,
+}
+
+impl Version {
+    /// Version of `MAGIC_BYTES`
+    pub const CURRENT: Version = Version {
+        major: VERSION,
+        patch: 0,
+    };
+
+    /// Parse magic bytes. Fails for other formats and unsupported major versions.
+    pub fn from_magic_bytes(magic_bytes: &[u8; 8]) -> Result<Version> {
+        if magic_bytes[0..3] != MAGIC_BYTES[0..3] || magic_bytes[4..7] != MAGIC_BYTES[4..7] {
+            return Err(Error::MagicBytes(*magic_bytes));
+        }
+        let version = Version {
+            major: magic_bytes[3],
+            patch: magic_bytes[7],
+        };
+        if !version.is_supported() {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        Ok(version)
+    }
+
+    /// Returns true, if files of this version can be read
+    pub fn is_supported(&self) -> bool {
+        self.major == 2 || self.major == VERSION
+    }
+
+    /// Leaf nodes contain feature numbers, which are resolved with a feature offsets table
+    pub(crate) fn has_feature_offsets(&self) -> bool {
+        self.major == 2
+    }
+
+    /// Size of the index section, which is 0 for datasets without index
+    pub(crate) fn index_size(&self, num_items: usize, node_size: u16) -> Result<usize> {
+        let size = dataset_index_size(num_items, node_size)?;
+        if size > 0 && self.has_feature_offsets() {
+            return num_items
+                .checked_mul(8)
+                .and_then(|offsets_size| offsets_size.checked_add(size))
+                .ok_or(Error::IndexTooLarge(num_items));
+        }
+        Ok(size)
+    }
+}
+
+#[cfg(test)]
+use crate::file_reader::FgbReader;
+#[cfg(test)]
+use byteorder::{ByteOrder, LittleEndian};
+
+/// Convert indexed version 3 file to version 2
+#[cfg(test)]
+fn file_v2(data: &[u8]) -> Result<Vec<u8>> {
+    let mut reader = std::io::Cursor::new(data);
+    let mut fgb = FgbReader::open(&mut reader)?;
+    let count = fgb.select_all()?;
+    let header_len = 12 + LittleEndian::read_u32(&data[8..12]) as usize;
+    let nodes_size = dataset_index_size(count, fgb.header().index_node_size())?;
+    let mut features = Vec::new();
+    let mut offsets = Vec::new();
+    while let Some(feature) = fgb.next()? {
+        // size prefixed buffer
+        offsets.push(features.len() as u64);
+        features.extend_from_slice(&feature.feature_buf);
+    }
+
+    let mut out = data[..header_len + nodes_size].to_vec();
+    out[3] = 2;
+    // leaf offsets of version 2 are feature numbers
+    let leaves = out.len() - count * 40;
+    for feature in 0..count {
+        LittleEndian::write_u64(&mut out[leaves + feature * 40 + 32..], feature as u64);
+    }
+    for offset in offsets {
+        out.extend_from_slice(&offset.to_le_bytes());
+    }
+    out.extend_from_slice(&features);
+    Ok(out)
+}
+
+#[test]
+fn parse_versions() {
+    let magic = |major: u8, patch: u8| [b'f', b'g', b'b', major, b'f', b'g', b'b', patch];
+    assert_eq!(
+        Version::from_magic_bytes(&MAGIC_BYTES).unwrap(),
+        Version::CURRENT
+    );
+    assert_eq!(
+        Version::from_magic_bytes(&magic(3, 1)).unwrap(),
+        Version { major: 3, patch: 1 }
+    );
+    assert!(Version::from_magic_bytes(&magic(2, 0)).is_ok());
+    match Version::from_magic_bytes(&magic(4, 0)) {
+        Err(e @ Error::UnsupportedVersion(_)) => {
+            assert_eq!(e.to_string(), "unsupported version 4")
+        }
+        _ => panic!("unsupported version expected"),
+    }
+    assert!(matches!(
+        Version::from_magic_bytes(b"{\"type\":"),
+        Err(Error::MagicBytes(_))
+    ));
+}
+
+#[test]
+fn read_v2() -> Result<()> {
+    let data = std::fs::read("../../test/data/countries.fgb")?;
+    let data_v2 = file_v2(&data)?;
+    let mut reader = std::io::Cursor::new(&data);
+    let mut fgb = FgbReader::open(&mut reader)?;
+    let mut reader_v2 = std::io::Cursor::new(&data_v2);
+    let mut fgb_v2 = FgbReader::open(&mut reader_v2)?;
+    assert_eq!(fgb.version(), Version::CURRENT);
+    assert_eq!(fgb_v2.version(), Version { major: 2, patch: 0 });
+
+    let geometry_type = crate::GeometryType::MultiPolygon;
+    assert_eq!(fgb_v2.select_all()?, fgb.select_all()?);
+    while let Some(feature) = fgb.next()? {
+        let feature_v2 = fgb_v2.next()?.unwrap();
+        assert_eq!(feature_v2.properties()?, feature.properties()?);
+        assert_eq!(
+            feature_v2
+                .geometry()
+                .unwrap()
+                .to_owned_geometry(geometry_type)?,
+            feature
+                .geometry()
+                .unwrap()
+                .to_owned_geometry(geometry_type)?
+        );
+    }
+    assert!(fgb_v2.next()?.is_none());
+
+    assert_eq!(fgb_v2.select_bbox(8.8, 47.2, 9.5, 55.3)?, 6);
+    let mut names = Vec::new();
+    while let Some(feature) = fgb_v2.next()? {
+        names.push(feature.properties()?["name"].clone());
+    }
+    assert!(names.contains(&"Denmark".to_string()));
+
+    fgb_v2.select_bbox_lazy(8.8, 47.2, 9.5, 55.3)?;
+    let mut lazy_names = Vec::new();
+    while let Some(feature) = fgb_v2.next()? {
+        lazy_names.push(feature.properties()?["name"].clone());
+    }
+    lazy_names.sort();
+    names.sort();
+    assert_eq!(lazy_names, names);
+    Ok(())
+}
+
+#[test]
+fn read_v2_geoserver() -> Result<()> {
+    // written by GeoServer with a FlatGeobuf 2 release, without index
+    let data = std::fs::read("../../test/data/topp_states.fgb")?;
+    let mut reader = std::io::Cursor::new(&data);
+    let mut fgb = FgbReader::open(&mut reader)?;
+    assert_eq!(fgb.version(), Version { major: 2, patch: 0 });
+    assert_eq!(fgb.header().index_node_size(), 0);
+    assert!(matches!(
+        fgb.select_bbox(-90.0, 40.0, -88.0, 42.0),
+        Err(Error::NoIndex)
+    ));
+
+    let geometry_type = fgb.header().geometry_type();
+    assert_eq!(fgb.select_all()?, 49);
+    let mut names = Vec::new();
+    while let Some(feature) = fgb.next()? {
+        let geometry = feature.geometry().unwrap();
+        match geometry.to_owned_geometry(geometry_type)? {
+            crate::OwnedGeometry::MultiPolygon(polygons) => assert!(!polygons[0][0].is_empty()),
+            _ => panic!("multi-polygon expected"),
+        }
+        names.push(feature.properties()?["STATE_NAME"].clone());
+    }
+    assert_eq!(names.len(), 49);
+    assert_eq!(
+        names[0..3],
+        ["Illinois", "District of Columbia", "Delaware"]
+    );
+    Ok(())
+}
+
+#[test]
+fn verify_v2() -> Result<()> {
+    let data = std::fs::read("../../test/data/countries.fgb")?;
+    let report = crate::verify(&mut std::io::Cursor::new(file_v2(&data)?))?;
+    assert_eq!(report.problems, vec![]);
+    assert_eq!(report.features_count, 179);
+
+    let data = std::fs::read("../../test/data/topp_states.fgb")?;
+    let report = crate::verify(&mut std::io::Cursor::new(data))?;
+    assert_eq!(report.problems, vec![]);
+    assert_eq!(report.features_count, 49);
+    Ok(())
+}
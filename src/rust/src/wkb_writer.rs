@@ -0,0 +1,251 @@
+//! WKB encoding in [ISO](https://www.ogc.org/standards/sfa) and
+//! [PostGIS EWKB](https://postgis.net/docs/using_postgis_dbmanagement.html#EWKB_EWKT) flavour.
+//!
+//! Only the Z and M dimensions are encoded. Missing values are written as NaN.
+
+use crate::feature_generated::flat_geobuf::*;
+use crate::header_generated::flat_geobuf::*;
+use crate::owned_geometry::{Coordinate, OwnedGeometry};
+use crate::properties_reader::FgbFeature;
+use geozero::error::{GeozeroError, Result};
+use geozero::CoordDimensions;
+
+#[derive(Clone, Copy, PartialEq)]
+enum WkbDialect {
+    Iso,
+    Ewkb,
+}
+
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+struct WkbWriter {
+    out: Vec<u8>,
+    dims: CoordDimensions,
+    dialect: WkbDialect,
+}
+
+impl WkbWriter {
+    fn header(&mut self, geometry_type: GeometryType, srid: Option<i32>) {
+        // little endian
+        self.out.push(1);
+        let base = geometry_type.0 as u32;
+        let type_code = match self.dialect {
+            WkbDialect::Iso => {
+                base + if self.dims.z { 1000 } else { 0 } + if self.dims.m { 2000 } else { 0 }
+            }
+            WkbDialect::Ewkb => {
+                base | if self.dims.z { EWKB_Z } else { 0 }
+                    | if self.dims.m { EWKB_M } else { 0 }
+                    | if srid.is_some() { EWKB_SRID } else { 0 }
+            }
+        };
+        self.out.extend_from_slice(&type_code.to_le_bytes());
+        if let (WkbDialect::Ewkb, Some(srid)) = (self.dialect, srid) {
+            self.out.extend_from_slice(&srid.to_le_bytes());
+        }
+    }
+
+    fn count(&mut self, n: usize) {
+        self.out.extend_from_slice(&(n as u32).to_le_bytes());
+    }
+
+    fn coord(&mut self, coord: &Coordinate) {
+        self.out.extend_from_slice(&coord.x.to_le_bytes());
+        self.out.extend_from_slice(&coord.y.to_le_bytes());
+        if self.dims.z {
+            let z = coord.z.unwrap_or(f64::NAN);
+            self.out.extend_from_slice(&z.to_le_bytes());
+        }
+        if self.dims.m {
+            let m = coord.m.unwrap_or(f64::NAN);
+            self.out.extend_from_slice(&m.to_le_bytes());
+        }
+    }
+
+    fn coords(&mut self, coords: &[Coordinate]) {
+        self.count(coords.len());
+        for coord in coords {
+            self.coord(coord);
+        }
+    }
+
+    fn rings(&mut self, rings: &[Vec<Coordinate>]) {
+        self.count(rings.len());
+        for ring in rings {
+            self.coords(ring);
+        }
+    }
+
+    fn geometry(&mut self, geometry: &OwnedGeometry, srid: Option<i32>) {
+        self.header(geometry.geometry_type(), srid);
+        match geometry {
+            OwnedGeometry::Point(coord) => self.coord(coord),
+            OwnedGeometry::LineString(coords) | OwnedGeometry::CircularString(coords) => {
+                self.coords(coords)
+            }
+            OwnedGeometry::Polygon(rings) => self.rings(rings),
+            OwnedGeometry::Triangle(ring) => {
+                self.count(1);
+                self.coords(ring);
+            }
+            OwnedGeometry::MultiPoint(coords) => {
+                self.count(coords.len());
+                for coord in coords {
+                    self.geometry(&OwnedGeometry::Point(*coord), None);
+                }
+            }
+            OwnedGeometry::MultiLineString(lines) => {
+                self.count(lines.len());
+                for line in lines {
+                    self.header(GeometryType::LineString, None);
+                    self.coords(line);
+                }
+            }
+            OwnedGeometry::MultiPolygon(polygons) | OwnedGeometry::PolyhedralSurface(polygons) => {
+                self.count(polygons.len());
+                for rings in polygons {
+                    self.header(GeometryType::Polygon, None);
+                    self.rings(rings);
+                }
+            }
+            OwnedGeometry::TIN(triangles) => {
+                self.count(triangles.len());
+                for ring in triangles {
+                    self.header(GeometryType::Triangle, None);
+                    self.count(1);
+                    self.coords(ring);
+                }
+            }
+            OwnedGeometry::GeometryCollection(geometries)
+            | OwnedGeometry::CompoundCurve(geometries)
+            | OwnedGeometry::CurvePolygon(geometries)
+            | OwnedGeometry::MultiCurve(geometries)
+            | OwnedGeometry::MultiSurface(geometries) => {
+                self.count(geometries.len());
+                for geometry in geometries {
+                    self.geometry(geometry, None);
+                }
+            }
+        }
+    }
+}
+
+fn write_wkb(
+    geometry: &OwnedGeometry,
+    dims: CoordDimensions,
+    dialect: WkbDialect,
+    srid: Option<i32>,
+) -> Vec<u8> {
+    let mut writer = WkbWriter {
+        out: Vec::new(),
+        dims,
+        dialect,
+    };
+    writer.geometry(geometry, srid);
+    writer.out
+}
+
+impl OwnedGeometry {
+    /// Encode as ISO WKB (little endian)
+    pub fn to_wkb(&self, dims: CoordDimensions) -> Vec<u8> {
+        write_wkb(self, dims, WkbDialect::Iso, None)
+    }
+    /// Encode as PostGIS EWKB (little endian)
+    pub fn to_ewkb(&self, dims: CoordDimensions, srid: Option<i32>) -> Vec<u8> {
+        write_wkb(self, dims, WkbDialect::Ewkb, srid)
+    }
+}
+
+impl Geometry<'_> {
+    /// Encode as ISO WKB (little endian)
+    pub fn to_wkb(&self, geometry_type: GeometryType, dims: CoordDimensions) -> Result<Vec<u8>> {
+        Ok(self.to_owned_geometry(geometry_type)?.to_wkb(dims))
+    }
+    /// Encode as PostGIS EWKB (little endian)
+    pub fn to_ewkb(
+        &self,
+        geometry_type: GeometryType,
+        dims: CoordDimensions,
+        srid: Option<i32>,
+    ) -> Result<Vec<u8>> {
+        Ok(self.to_owned_geometry(geometry_type)?.to_ewkb(dims, srid))
+    }
+}
+
+impl FgbFeature {
+    /// Feature geometry as ISO WKB
+    pub fn geometry_wkb(&self, dims: CoordDimensions) -> Result<Vec<u8>> {
+        let geometry = self.geometry().ok_or(GeozeroError::GeometryFormat)?;
+        geometry.to_wkb(self.header().geometry_type(), dims)
+    }
+    /// Feature geometry as EWKB with SRID of header CRS
+    pub fn geometry_ewkb(&self, dims: CoordDimensions) -> Result<Vec<u8>> {
+        let geometry = self.geometry().ok_or(GeozeroError::GeometryFormat)?;
+        let header = self.header();
+        let srid = header.crs().map(|crs| crs.code()).filter(|code| *code != 0);
+        geometry.to_ewkb(header.geometry_type(), dims, srid)
+    }
+}
+
+#[cfg(test)]
+const XY: CoordDimensions = CoordDimensions {
+    z: false,
+    m: false,
+    t: false,
+    tm: false,
+};
+
+#[test]
+fn point_wkb() {
+    let point = OwnedGeometry::Point(Coordinate::xy(1.0, 2.0));
+    // POINT(1 2)
+    assert_eq!(
+        hex::encode(point.to_wkb(XY)),
+        "0101000000000000000000f03f0000000000000040"
+    );
+    // SRID=4326;POINT(1 2)
+    assert_eq!(
+        hex::encode(point.to_ewkb(XY, Some(4326))),
+        "0101000020e6100000000000000000f03f0000000000000040"
+    );
+    let point = OwnedGeometry::Point(Coordinate {
+        z: Some(3.0),
+        ..Coordinate::xy(1.0, 2.0)
+    });
+    let xyz = CoordDimensions { z: true, ..XY };
+    // POINT Z(1 2 3)
+    assert_eq!(
+        hex::encode(point.to_wkb(xyz)),
+        "01e9030000000000000000f03f00000000000000400000000000000840"
+    );
+    // POINT Z(1 2 3) without SRID
+    assert_eq!(
+        hex::encode(point.to_ewkb(xyz, None)),
+        "0101000080000000000000f03f00000000000000400000000000000840"
+    );
+}
+
+#[test]
+fn curve_wkb() {
+    let xym = CoordDimensions { m: true, ..XY };
+    let geometry = OwnedGeometry::CompoundCurve(vec![
+        OwnedGeometry::CircularString(vec![
+            Coordinate::xy(0.0, 0.0),
+            Coordinate::xy(1.0, 1.0),
+            Coordinate::xy(2.0, 0.0),
+        ]),
+        OwnedGeometry::LineString(vec![Coordinate::xy(2.0, 0.0), Coordinate::xy(0.0, 0.0)]),
+    ]);
+    let wkb = geometry.to_wkb(xym);
+    // COMPOUNDCURVE M with 2 segments
+    assert_eq!(hex::encode(&wkb[..9]), "01d907000002000000");
+    // CIRCULARSTRING M with 3 points
+    assert_eq!(hex::encode(&wkb[9..18]), "01d807000003000000");
+    assert_eq!(wkb.len(), 9 + 9 + 3 * 24 + 9 + 2 * 24);
+    // Missing M value
+    let mut m = [0; 8];
+    m.copy_from_slice(&wkb[34..42]);
+    assert!(f64::from_le_bytes(m).is_nan());
+}
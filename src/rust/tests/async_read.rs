@@ -0,0 +1,78 @@
+use flatgeobuf::*;
+use geozero::error::Result;
+use geozero_core::geojson::GeoJsonWriter;
+use tokio::fs::File;
+use tokio::runtime::Runtime;
+
+async fn async_read() -> Result<()> {
+    let file = File::open("../../test/data/countries.fgb").await?;
+    let mut fgb = AsyncFgbReader::open(file).await?;
+    assert_eq!(fgb.header().geometry_type(), GeometryType::MultiPolygon);
+    assert_eq!(fgb.header().features_count(), 179);
+    assert_eq!(fgb.select_all().await?, 179);
+    let mut cnt = 0;
+    while let Some(feature) = fgb.next().await? {
+        if cnt == 0 {
+            assert_eq!(feature.properties()?["name"], "Antarctica".to_string());
+        }
+        cnt += 1;
+    }
+    assert_eq!(cnt, 179);
+    Ok(())
+}
+
+#[test]
+fn async_file_reader() {
+    assert!(Runtime::new().unwrap().block_on(async_read()).is_ok());
+}
+
+async fn async_bbox_read() -> Result<()> {
+    let file = File::open("../../test/data/countries.fgb").await?;
+    let mut fgb = AsyncFgbReader::open(file).await?;
+    assert_eq!(fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?, 6);
+    let feature = fgb.next().await?.unwrap();
+    assert_eq!(feature.properties()?["name"], "Denmark".to_string());
+
+    // Select again with the same reader
+    assert_eq!(fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?, 6);
+    let mut json_data: Vec<u8> = Vec::new();
+    let mut json = GeoJsonWriter::new(&mut json_data);
+    fgb.process_features(&mut json).await?;
+    assert_eq!(
+        &std::str::from_utf8(&json_data).unwrap()[..239],
+        r#"{
+"type": "FeatureCollection",
+"name": "countries",
+"features": [{"type": "Feature", "properties": {"id": "DNK", "name": "Denmark"}, "geometry": {"type": "MultiPolygon", "coordinates": [[[[12.690006,55.609991],[12.089991,54.800015],[11.043"#
+    );
+    Ok(())
+}
+
+#[test]
+fn async_bbox_file_reader() {
+    assert!(Runtime::new().unwrap().block_on(async_bbox_read()).is_ok());
+}
+
+async fn async_search_bounds() -> Result<()> {
+    let mut file = File::open("../../test/data/countries.fgb").await?;
+    file.seek(std::io::SeekFrom::Start(12 + 604)).await?;
+    let items = PackedRTree::async_stream_search(&mut file, 179, 16, 8.8, 47.2, 9.5, 55.3).await?;
+    assert_eq!(items.len(), 6);
+
+    let file = File::open("../../test/data/countries.fgb").await?;
+    let mut fgb = AsyncFgbReader::open(file).await?;
+    fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?;
+    for item in &items {
+        let feature = fgb.next().await?.unwrap();
+        assert_eq!(feature.bbox()?.to_array(), item.bbox.to_array());
+    }
+    Ok(())
+}
+
+#[test]
+fn async_search_result_bounds() {
+    Runtime::new()
+        .unwrap()
+        .block_on(async_search_bounds())
+        .unwrap();
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "geo-types")]
+
+use flatgeobuf::*;
+use geozero::error::Result;
+use std::fs::File;
+use std::io::BufReader;
+
+#[test]
+fn countries_to_geo() -> Result<()> {
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    fgb.select_all()?;
+    let feature = fgb.next()?.unwrap();
+    match feature.geometry_geo()? {
+        geo_types::Geometry::MultiPolygon(mpoly) => {
+            assert_eq!(mpoly.0.len(), 8);
+            let exterior = mpoly.0[0].exterior();
+            assert_eq!(exterior.0.len(), 13);
+            assert_eq!((exterior.0[0].x, exterior.0[0].y), (-59.572095, -80.040179));
+            assert_eq!(exterior.0[0], exterior.0[12]);
+            let num_vertices: usize = mpoly.0.iter().map(|p| p.exterior().0.len()).sum();
+            assert_eq!(num_vertices, 658);
+        }
+        _ => panic!("MultiPolygon expected"),
+    }
+    let mut cnt = 1;
+    while let Some(feature) = fgb.next()? {
+        feature.geometry_geo()?;
+        cnt += 1;
+    }
+    assert_eq!(cnt, 179);
+    Ok(())
+}
+
+#[test]
+fn unsupported_type() -> Result<()> {
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    fgb.select_all()?;
+    let feature = fgb.next()?.unwrap();
+    let geometry = feature.geometry().unwrap();
+    assert!(geometry.to_geo(GeometryType::CurvePolygon).is_err());
+    Ok(())
+}
+
+#[test]
+fn odd_coordinate_count() {
+    let mut fbb = flatbuffers::FlatBufferBuilder::new();
+    let xy = fbb.create_vector(&[1.0, 2.0, 3.0]);
+    let geometry = Geometry::create(
+        &mut fbb,
+        &GeometryArgs {
+            xy: Some(xy),
+            type_: GeometryType::LineString,
+            ..Default::default()
+        },
+    );
+    let feature = Feature::create(
+        &mut fbb,
+        &FeatureArgs {
+            geometry: Some(geometry),
+            ..Default::default()
+        },
+    );
+    fbb.finish_size_prefixed(feature, None);
+    let feature = size_prefixed_root_as_feature(fbb.finished_data()).unwrap();
+    let geometry = feature.geometry().unwrap();
+    assert!(matches!(
+        geometry.to_geo(GeometryType::LineString),
+        Err(geozero::error::GeozeroError::Coord)
+    ));
+    assert!(matches!(
+        geometry.to_geo(GeometryType::MultiPoint),
+        Err(geozero::error::GeozeroError::Coord)
+    ));
+}
@@ -1,5 +1,10 @@
 use flatgeobuf::*;
 use geozero::error::Result;
+use std::cmp::min;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use tokio::runtime::Runtime;
 
 async fn http_read_async() -> Result<()> {
@@ -41,6 +46,211 @@ fn http_bbox_read() {
         .is_ok());
 }
 
+async fn http_index_read_async() -> Result<()> {
+    let url =
+        "https://raw.githubusercontent.com/bjornharrtell/flatgeobuf/master/test/data/countries.fgb";
+    for levels in &[1, usize::MAX] {
+        let mut fgb = HttpFgbReader::open(url).await?;
+        fgb.load_index(*levels).await?;
+        for _ in 0..2 {
+            assert_eq!(fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?, 6);
+            let feature = fgb.next().await?.unwrap();
+            let props = feature.properties()?;
+            assert_eq!(props["name"], "Denmark".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn http_index_read() {
+    assert!(Runtime::new()
+        .unwrap()
+        .block_on(http_index_read_async())
+        .is_ok());
+}
+
+async fn http_stats_async() -> Result<()> {
+    let url =
+        "https://raw.githubusercontent.com/bjornharrtell/flatgeobuf/master/test/data/countries.fgb";
+    let mut fgb = HttpFgbReader::open(url).await?;
+    let stats = fgb.stats().clone();
+    assert!(stats.requests > 0);
+    assert!(stats.bytes >= 12 + fgb.header().name().unwrap().len());
+    fgb.reset_stats();
+    fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?;
+    while let Some(_feature) = fgb.next().await? {}
+    let stats = fgb.stats();
+    assert!(stats.requests > 0);
+    assert_eq!(stats.cache_hits, 0);
+    assert_eq!(stats.header_time.as_nanos(), 0);
+    assert!(stats.index_time.as_nanos() > 0);
+    assert!(stats.feature_time.as_nanos() > 0);
+    Ok(())
+}
+
+#[test]
+fn http_stats() {
+    assert!(Runtime::new().unwrap().block_on(http_stats_async()).is_ok());
+}
+
+async fn http_strategy_async() -> Result<()> {
+    let url =
+        "https://raw.githubusercontent.com/bjornharrtell/flatgeobuf/master/test/data/countries.fgb";
+    let strategy = HttpStrategy {
+        header_prefetch: 1024,
+        index_chunk_size: 4096,
+        feature_read_ahead: 65536,
+        max_buffer_size: 131072,
+        adaptive: true,
+    };
+    let mut fgb = HttpFgbReader::from_client(BufferedHttpClient::new(url), strategy).await?;
+    // Header of countries.fgb fits into first request
+    assert_eq!(fgb.stats().requests, 1);
+    fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?;
+    let mut names = Vec::new();
+    while let Some(feature) = fgb.next().await? {
+        names.push(feature.properties()?["name"].clone());
+    }
+    assert_eq!(names.len(), 6);
+    assert_eq!(names[0], "Denmark".to_string());
+    // Default strategy reads the whole file (205680 bytes)
+    assert!(fgb.stats().bytes < 205680);
+    Ok(())
+}
+
+#[test]
+fn http_strategy() {
+    assert!(Runtime::new()
+        .unwrap()
+        .block_on(http_strategy_async())
+        .is_ok());
+}
+
+async fn file_url_read_async() -> Result<()> {
+    let path = std::fs::canonicalize("../../test/data/countries.fgb")?;
+    let url = format!("file://{}", path.display());
+    let mut fgb = HttpFgbReader::open(&url).await?;
+    assert_eq!(fgb.header().features_count(), 179);
+    assert_eq!(fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?, 6);
+    let feature = fgb.next().await?.unwrap();
+    let props = feature.properties()?;
+    assert_eq!(props["name"], "Denmark".to_string());
+    assert_eq!(fgb.stats().cache_hits, 0);
+    Ok(())
+}
+
+#[test]
+fn file_url_read() {
+    assert!(Runtime::new()
+        .unwrap()
+        .block_on(file_url_read_async())
+        .is_ok());
+}
+
+async fn file_url_adaptive_async() -> Result<()> {
+    let path = std::fs::canonicalize("../../test/data/countries.fgb")?;
+    let url = format!("file://{}", path.display());
+    let strategy = HttpStrategy {
+        feature_read_ahead: 65536,
+        adaptive: true,
+        ..Default::default()
+    };
+    let mut fgb = HttpFgbReader::from_client(BufferedHttpClient::new(&url), strategy).await?;
+    fgb.select_bbox(-180.0, -90.0, 180.0, 90.0).await?;
+    fgb.reset_stats();
+    let mut names = Vec::new();
+    while let Some(feature) = fgb.next().await? {
+        names.push(feature.properties()?["name"].clone());
+    }
+    assert_eq!(names.len(), 179);
+    assert_eq!(names[0], "Antarctica".to_string());
+    // read-ahead windows of 64 KB cover many features
+    assert_eq!(fgb.stats().requests, 4);
+    Ok(())
+}
+
+#[test]
+fn file_url_adaptive() {
+    Runtime::new()
+        .unwrap()
+        .block_on(file_url_adaptive_async())
+        .unwrap();
+}
+
+async fn http_search_bounds_async() -> Result<()> {
+    let path = std::fs::canonicalize("../../test/data/countries.fgb")?;
+    let url = format!("file://{}", path.display());
+    let mut client = BufferedHttpClient::new(&url);
+    let items =
+        PackedRTree::http_stream_search(&mut client, 12 + 604, 179, 16, 8.8, 47.2, 9.5, 55.3)
+            .await?;
+    assert_eq!(items.len(), 6);
+    let mut fgb = HttpFgbReader::open(&url).await?;
+    fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?;
+    for item in &items {
+        let feature = fgb.next().await?.unwrap();
+        assert_eq!(feature.bbox()?.to_array(), item.bbox.to_array());
+    }
+    Ok(())
+}
+
+#[test]
+fn http_search_bounds() {
+    Runtime::new()
+        .unwrap()
+        .block_on(http_search_bounds_async())
+        .unwrap();
+}
+
+async fn file_url_escaped_read_async() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("natural earth").join("countries 100%.fgb");
+    std::fs::create_dir(path.parent().unwrap())?;
+    std::fs::copy("../../test/data/countries.fgb", &path)?;
+    let url = reqwest::Url::from_file_path(&path).unwrap();
+    assert!(url.as_str().contains("countries%20100%25.fgb"));
+    let mut fgb = HttpFgbReader::open(url.as_str()).await?;
+    assert_eq!(fgb.header().features_count(), 179);
+    assert_eq!(fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?, 6);
+    Ok(())
+}
+
+#[test]
+fn file_url_escaped_read() {
+    assert!(Runtime::new()
+        .unwrap()
+        .block_on(file_url_escaped_read_async())
+        .is_ok());
+}
+
+async fn s3_read_async() -> Result<()> {
+    // Requires S3-compatible server, e.g.
+    // docker run -p 9000:9000 -e MINIO_ROOT_USER=minioadmin -e MINIO_ROOT_PASSWORD=minioadmin minio/minio server /data
+    // with countries.fgb uploaded to bucket `flatgeobuf`
+    let config = S3Config {
+        access_key_id: "minioadmin".to_string(),
+        secret_access_key: "minioadmin".to_string(),
+        session_token: None,
+        region: "us-east-1".to_string(),
+        endpoint: Some("http://localhost:9000".to_string()),
+    };
+    let client = BufferedHttpClient::with_s3_config("s3://flatgeobuf/countries.fgb", config);
+    let mut fgb = HttpFgbReader::from_client(client, HttpStrategy::default()).await?;
+    assert_eq!(fgb.header().features_count(), 179);
+    fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?;
+    let feature = fgb.next().await?.unwrap();
+    let props = feature.properties()?;
+    assert_eq!(props["name"], "Denmark".to_string());
+    Ok(())
+}
+
+#[test]
+#[ignore]
+fn s3_read() {
+    assert!(Runtime::new().unwrap().block_on(s3_read_async()).is_ok());
+}
+
 async fn http_bbox_big_async() -> Result<()> {
     let url = "https://pkg.sourcepole.ch/osm-buildings-ch.fgb";
     let mut fgb = HttpFgbReader::open(url).await?;
@@ -63,6 +273,110 @@ fn http_bbox_big() {
         .is_ok());
 }
 
+async fn http_cache_read_async() -> Result<()> {
+    let url =
+        "https://raw.githubusercontent.com/bjornharrtell/flatgeobuf/master/test/data/countries.fgb";
+    let dir = tempfile::tempdir()?;
+    let cache = HttpCache::open(dir.path(), 10 * 1024 * 1024)?;
+    for run in 0..2 {
+        let mut fgb = HttpFgbReader::open_with_cache(url, cache.clone()).await?;
+        fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?;
+        let feature = fgb.next().await?.unwrap();
+        let props = feature.properties()?;
+        assert_eq!(props["name"], "Denmark".to_string());
+        assert!(cache.size() > 0);
+        if run > 0 {
+            assert!(fgb.stats().cache_hits > 0);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn http_cache_read() {
+    assert!(Runtime::new()
+        .unwrap()
+        .block_on(http_cache_read_async())
+        .is_ok());
+}
+
+/// Minimal HTTP server answering range requests for `data` with an ETag.
+/// Returns the URL and the number of requests served.
+fn serve_ranges(data: Vec<u8>, etag: &'static str) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/countries.fgb", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let served = requests.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut range = (0, data.len() - 1);
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("range: bytes=") {
+                    let mut bounds = value.trim().split('-').map(|v| v.parse().unwrap());
+                    range = (bounds.next().unwrap(), bounds.next().unwrap());
+                }
+            }
+            served.fetch_add(1, Ordering::SeqCst);
+            let end = min(range.1 + 1, data.len());
+            let body = &data[min(range.0, end)..end];
+            write!(
+                stream,
+                "HTTP/1.1 206 Partial Content\r\nETag: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                etag,
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(body).unwrap();
+        }
+    });
+    (url, requests)
+}
+
+async fn http_cache_sessions_async() -> Result<()> {
+    let data = std::fs::read("../../test/data/countries.fgb")?;
+    let (url, requests) = serve_ranges(data, "\"v1\"");
+    let dir = tempfile::tempdir()?;
+    let mut names = Vec::new();
+    let mut served = 0;
+    for session in 0..2 {
+        // cache opened like in a new process
+        let cache = HttpCache::open(dir.path(), 10 * 1024 * 1024)?;
+        let mut fgb = HttpFgbReader::open_with_cache(&url, cache).await?;
+        fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await?;
+        let mut session_names = Vec::new();
+        while let Some(feature) = fgb.next().await? {
+            session_names.push(feature.properties()?["name"].clone());
+        }
+        if session == 0 {
+            assert!(fgb.stats().requests > 0);
+            served = requests.load(Ordering::SeqCst);
+            names = session_names;
+        } else {
+            // served from cache with the stored ETag
+            assert_eq!(fgb.stats().requests, 0);
+            assert!(fgb.stats().cache_hits > 0);
+            assert_eq!(session_names, names);
+        }
+    }
+    assert_eq!(names.len(), 6);
+    assert_eq!(requests.load(Ordering::SeqCst), served);
+    Ok(())
+}
+
+#[test]
+fn http_cache_sessions() {
+    Runtime::new()
+        .unwrap()
+        .block_on(http_cache_sessions_async())
+        .unwrap();
+}
+
 async fn http_err_async() {
     let url =
         "https://raw.githubusercontent.com/bjornharrtell/flatgeobuf/master/test/data/wrong.fgb";
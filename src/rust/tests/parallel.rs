@@ -0,0 +1,70 @@
+#![cfg(feature = "rayon")]
+
+use flatgeobuf::*;
+use geozero::error::Result;
+use geozero::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+use std::fs::File;
+use std::io::BufReader;
+
+#[derive(Default)]
+struct VertexCounter {
+    features: usize,
+    vertices: usize,
+}
+
+impl GeomProcessor for VertexCounter {
+    fn xy(&mut self, _x: f64, _y: f64, _idx: usize) -> Result<()> {
+        self.vertices += 1;
+        Ok(())
+    }
+}
+
+impl PropertyProcessor for VertexCounter {}
+
+impl FeatureProcessor for VertexCounter {
+    fn feature_end(&mut self, _idx: u64) -> Result<()> {
+        self.features += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn par_map_features() -> Result<()> {
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    fgb.select_all()?;
+    let names = fgb.par_map_features(|feature, _idx| Ok(feature.properties()?["name"].clone()))?;
+    assert_eq!(names.len(), 179);
+    assert_eq!(names[0], "Antarctica");
+
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    fgb.select_all()?;
+    let mut sequential = Vec::new();
+    while let Some(feature) = fgb.next()? {
+        sequential.push(feature.properties()?["name"].clone());
+    }
+    assert_eq!(names, sequential);
+    Ok(())
+}
+
+#[test]
+fn par_process_features() -> Result<()> {
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    fgb.select_all()?;
+    let counters = fgb.par_process_features(VertexCounter::default)?;
+    assert!(!counters.is_empty());
+    assert_eq!(counters.iter().map(|c| c.features).sum::<usize>(), 179);
+
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    fgb.select_all()?;
+    let mut counter = VertexCounter::default();
+    fgb.process_features(&mut counter)?;
+    assert_eq!(
+        counters.iter().map(|c| c.vertices).sum::<usize>(),
+        counter.vertices
+    );
+    Ok(())
+}
@@ -19,15 +19,16 @@ fn read_file_low_level() -> Result<()> {
     let header_size = u32::from_le_bytes(size_buf);
     assert_eq!(header_size, 604);
 
-    let mut header_buf = vec![0; header_size as usize];
-    reader.read_exact(&mut header_buf)?;
+    let mut header_buf = vec![0; 4 + header_size as usize];
+    header_buf[..4].copy_from_slice(&size_buf);
+    reader.read_exact(&mut header_buf[4..])?;
 
-    let header = get_root_as_header(&header_buf[..]);
+    let header = size_prefixed_root_as_header(&header_buf).unwrap();
     assert_eq!(header.name(), Some("countries"));
     assert!(header.envelope().is_some());
     assert_eq!(
-        header.envelope().unwrap().safe_slice(),
-        &[-180.0, -85.609038, 180.0, 83.64513]
+        header.envelope().unwrap().iter().collect::<Vec<_>>(),
+        vec![-180.0, -85.609038, 180.0, 83.64513]
     );
     assert_eq!(header.geometry_type(), GeometryType::MultiPolygon);
     assert_eq!(header.hasZ(), false);
@@ -48,17 +49,18 @@ fn read_file_low_level() -> Result<()> {
 
     // Skip index
     let index_size =
-        PackedRTree::index_size(header.features_count() as usize, header.index_node_size());
+        PackedRTree::index_size(header.features_count() as usize, header.index_node_size())?;
     reader.seek(SeekFrom::Current(index_size as i64))?;
 
     // Read first feature
     reader.read_exact(&mut size_buf)?;
     let feature_size = u32::from_le_bytes(size_buf);
     assert_eq!(feature_size, 10804);
-    let mut feature_buf = vec![0; feature_size as usize];
-    reader.read_exact(&mut feature_buf)?;
+    let mut feature_buf = vec![0; 4 + feature_size as usize];
+    feature_buf[..4].copy_from_slice(&size_buf);
+    reader.read_exact(&mut feature_buf[4..])?;
 
-    let feature = get_root_as_feature(&feature_buf[..]);
+    let feature = size_prefixed_root_as_feature(&feature_buf).unwrap();
     assert!(feature.geometry().is_some());
     let geometry = feature.geometry().unwrap();
     assert_eq!(geometry.type_(), GeometryType::MultiPolygon);
@@ -163,13 +165,37 @@ fn bbox_file_reader() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn repeated_selections() -> Result<()> {
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    let read_names = |fgb: &mut FgbReader| -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        while let Some(feature) = fgb.next()? {
+            names.push(feature.properties()?["name"].clone());
+        }
+        Ok(names)
+    };
+
+    assert_eq!(fgb.select_all()?, 179);
+    let all = read_names(&mut fgb)?;
+    assert_eq!(all.len(), 179);
+    assert_eq!(fgb.select_bbox(8.8, 47.2, 9.5, 55.3)?, 6);
+    let selected = read_names(&mut fgb)?;
+    assert_eq!(selected[0], "Denmark");
+    assert_eq!(fgb.select_bbox(8.8, 47.2, 9.5, 55.3)?, 6);
+    assert_eq!(read_names(&mut fgb)?, selected);
+    assert_eq!(fgb.select_all()?, 179);
+    assert_eq!(read_names(&mut fgb)?, all);
+    Ok(())
+}
+
 #[test]
 fn magic_byte() -> Result<()> {
     let mut filein = BufReader::new(File::open("../../test/data/states.geojson")?);
-    assert_eq!(
-        FgbReader::open(&mut filein).err().unwrap().to_string(),
-        "geometry format"
-    );
+    let err = FgbReader::open(&mut filein).err().unwrap();
+    assert!(matches!(err, Error::MagicBytes(_)));
+    assert!(err.to_string().starts_with("invalid magic bytes"));
 
     Ok(())
 }
@@ -505,3 +531,344 @@ fn property_types() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn owned_geometry() -> Result<()> {
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    fgb.select_all()?;
+    let feature = fgb.next()?.unwrap();
+    let geometry = feature
+        .geometry()
+        .unwrap()
+        .to_owned_geometry(GeometryType::MultiPolygon)?;
+    if let OwnedGeometry::MultiPolygon(polygons) = &geometry {
+        assert_eq!(polygons.len(), 8);
+        assert_eq!(polygons[0][0][0], Coordinate::xy(-59.572095, -80.040179));
+        let num_vertices: usize = polygons.iter().flatten().map(|ring| ring.len()).sum();
+        assert_eq!(num_vertices, 658);
+    } else {
+        panic!("MultiPolygon expected");
+    }
+    // Geometry outlives feature buffer
+    let _ = fgb.next()?;
+    assert_eq!(geometry.geometry_type(), GeometryType::MultiPolygon);
+    Ok(())
+}
+
+#[test]
+fn ewkb_geometry() -> Result<()> {
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    fgb.select_all()?;
+    let feature = fgb.next()?.unwrap();
+    let dims = CoordDimensions {
+        z: false,
+        m: false,
+        t: false,
+        tm: false,
+    };
+    let wkb = feature.geometry_wkb(dims)?;
+    assert_eq!(&wkb[..9], &[1, 6, 0, 0, 0, 8, 0, 0, 0]);
+    let ewkb = feature.geometry_ewkb(dims)?;
+    // SRID=4326;MULTIPOLYGON with 8 polygons
+    assert_eq!(
+        &ewkb[..13],
+        &[1, 6, 0, 0, 0x20, 0xe6, 0x10, 0, 0, 8, 0, 0, 0]
+    );
+    assert_eq!(ewkb.len(), wkb.len() + 4);
+    // 8 polygon headers, 8 ring counts
+    assert_eq!(wkb.len(), 9 + 8 * 9 + 8 * 4 + 658 * 16);
+    Ok(())
+}
+
+#[test]
+fn feature_bbox() -> Result<()> {
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    let extent = NodeItem::new(-180.0, -85.609038, 180.0, 83.64513);
+    assert_eq!(fgb.envelope()?, Some(extent.clone()));
+
+    let header_len = 12 + 604;
+    let mut reader = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    reader.seek(SeekFrom::Start(header_len))?;
+    assert_eq!(PackedRTree::read_extent(&mut reader)?, extent);
+    reader.seek(SeekFrom::Start(header_len))?;
+    let items = PackedRTree::stream_search(&mut reader, 179, 16, 8.8, 47.2, 9.5, 55.3)?;
+
+    assert_eq!(fgb.select_bbox(8.8, 47.2, 9.5, 55.3)?, items.len());
+    for item in &items {
+        let feature = fgb.next()?.unwrap();
+        assert_eq!(feature.bbox()?.to_array(), item.bbox.to_array());
+        assert_eq!(item.bbox.offset() as usize, item.offset);
+    }
+    Ok(())
+}
+
+#[test]
+fn lazy_bbox_file_reader() -> Result<()> {
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    fgb.select_bbox(8.8, 47.2, 9.5, 55.3)?;
+    let mut names = Vec::new();
+    while let Some(feature) = fgb.next()? {
+        names.push(feature.properties()?["name"].clone());
+    }
+
+    fgb.select_bbox_lazy(8.8, 47.2, 9.5, 55.3)?;
+    let feature = fgb.next()?.unwrap();
+    assert_eq!(feature.properties()?["name"], "Denmark".to_string());
+    let mut lazy_names = vec![feature.properties()?["name"].clone()];
+    while let Some(feature) = fgb.next()? {
+        lazy_names.push(feature.properties()?["name"].clone());
+    }
+    assert_eq!(lazy_names, names);
+    Ok(())
+}
+
+#[test]
+fn search_order() -> Result<()> {
+    let mut reader = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let header_len = 12 + 604;
+    let bboxes = [
+        (8.8, 47.2, 9.5, 55.3),
+        (-180.0, -90.0, 180.0, 90.0),
+        (-20.0, -40.0, 60.0, 40.0),
+    ];
+    reader.seek(SeekFrom::Start(header_len))?;
+    let tree = PackedRTree::from_buf(&mut reader, 179, 16)?;
+    for (min_x, min_y, max_x, max_y) in bboxes.iter().cloned() {
+        let list = tree.search(min_x, min_y, max_x, max_y)?;
+        reader.seek(SeekFrom::Start(header_len))?;
+        let stream_list =
+            PackedRTree::stream_search(&mut reader, 179, 16, min_x, min_y, max_x, max_y)?;
+        let items = |list: &[SearchResultItem]| {
+            list.iter()
+                .map(|item| (item.offset, item.index))
+                .collect::<Vec<_>>()
+        };
+        assert!(!list.is_empty());
+        assert_eq!(items(&list), items(&stream_list));
+        assert!(list.windows(2).all(|w| w[0].offset < w[1].offset));
+        // repeated searches return identical results
+        assert_eq!(
+            items(&tree.search(min_x, min_y, max_x, max_y)?),
+            items(&list)
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn spatial_join() -> Result<()> {
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    let mut filein2 = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb2 = FgbReader::open(&mut filein2)?;
+
+    fgb.select_all()?;
+    let mut bboxes = Vec::new();
+    while let Some(feature) = fgb.next()? {
+        bboxes.push(feature.bbox()?);
+    }
+    let mut expected = Vec::new();
+    for (i, a) in bboxes.iter().enumerate() {
+        for (j, b) in bboxes.iter().enumerate() {
+            if a.intersects(b) {
+                expected.push((i, j));
+            }
+        }
+    }
+    expected.sort();
+
+    let mut filein = BufReader::new(File::open("../../test/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?;
+    let pairs = fgb.join(&mut fgb2)?;
+    let mut indices: Vec<_> = pairs.iter().map(|(a, b)| (a.index, b.index)).collect();
+    indices.sort();
+    assert_eq!(indices, expected);
+    assert!(pairs.windows(2).all(|w| w[0].0.offset <= w[1].0.offset));
+
+    let same_name = |a: &FgbFeature, b: &FgbFeature| -> Result<bool> {
+        Ok(a.properties()?["name"] == b.properties()?["name"])
+    };
+    let pairs = fgb.join_refined(&mut fgb2, same_name)?;
+    assert_eq!(pairs.len(), 179);
+    assert!(pairs.iter().all(|(a, b)| a.index == b.index));
+
+    // Reader selections still work after a join
+    assert_eq!(fgb.select_bbox(8.8, 47.2, 9.5, 55.3)?, 6);
+    assert_eq!(
+        fgb.next()?.unwrap().properties()?["name"],
+        "Denmark".to_string()
+    );
+    Ok(())
+}
+
+#[test]
+fn verify_buffers() -> Result<()> {
+    let data = std::fs::read("../../test/data/countries.fgb")?;
+    // corrupt root offset of header
+    let mut corrupt = data.clone();
+    corrupt[12..16].copy_from_slice(&0xffff_fff0u32.to_le_bytes());
+    let mut reader = std::io::Cursor::new(corrupt);
+    let err = FgbReader::open(&mut reader).err().unwrap();
+    assert!(err.to_string().contains("Invalid header"));
+
+    // corrupt root offset of second feature
+    let header_len = 12 + u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize;
+    let header = size_prefixed_root_as_header(&data[8..header_len]).unwrap();
+    let count = header.features_count() as usize;
+    let feature_base = header_len + PackedRTree::index_size(count, header.index_node_size())?;
+    let mut size_buf = [0; 4];
+    size_buf.copy_from_slice(&data[feature_base..feature_base + 4]);
+    let second = feature_base + 4 + u32::from_le_bytes(size_buf) as usize;
+    let mut corrupt = data.clone();
+    corrupt[second + 4..second + 8].copy_from_slice(&0xffff_fff0u32.to_le_bytes());
+    let mut reader = std::io::Cursor::new(corrupt);
+    let mut fgb = FgbReader::open(&mut reader)?;
+    fgb.select_all()?;
+    assert!(fgb.next()?.is_some());
+    let err = fgb.next().err().unwrap();
+    assert!(err.to_string().contains("Invalid feature"));
+    match err {
+        Error::InvalidFeature {
+            feature, offset, ..
+        } => assert_eq!((feature, offset), (1, second as u64)),
+        _ => panic!("invalid feature error expected"),
+    }
+
+    // trusted data
+    let mut reader = std::io::Cursor::new(data);
+    let mut fgb = FgbReader::open(&mut reader)?;
+    unsafe { fgb.set_verify(false) };
+    assert_eq!(fgb.select_all()?, 179);
+    let mut count = 0;
+    while fgb.next()?.is_some() {
+        count += 1;
+    }
+    assert_eq!(count, 179);
+    Ok(())
+}
+
+#[test]
+fn truncated_feature() -> Result<()> {
+    let mut data = std::fs::read("../../test/data/countries.fgb")?;
+    data.truncate(data.len() - 10);
+    let file_len = data.len();
+    let mut reader = std::io::Cursor::new(data);
+    let mut fgb = FgbReader::open(&mut reader)?;
+    fgb.select_all()?;
+    for _ in 0..178 {
+        fgb.next()?;
+    }
+    match fgb.next().err().unwrap() {
+        Error::TruncatedFeature {
+            feature,
+            offset,
+            expected,
+            actual,
+        } => {
+            assert_eq!(feature, 178);
+            assert_eq!(offset + actual as u64, file_len as u64);
+            assert_eq!(expected, actual + 10);
+        }
+        err => panic!("truncated feature error expected, got {}", err),
+    }
+    Ok(())
+}
+
+/// countries.fgb with index rebuilt for `node_size`
+fn countries_with_node_size(node_size: u16) -> Result<Vec<u8>> {
+    let data = std::fs::read("../../test/data/countries.fgb")?;
+    let header_len = 12 + u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize;
+    let header = size_prefixed_root_as_header(&data[8..header_len]).unwrap();
+    let count = header.features_count() as usize;
+    let feature_base = header_len + PackedRTree::index_size(count, header.index_node_size())?;
+    let value = |buf: &[u8], i: usize| {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&buf[i * 8..i * 8 + 8]);
+        bytes
+    };
+    let leaves: Vec<NodeItem> = data[feature_base - count * 40..feature_base]
+        .chunks(40)
+        .map(|node| {
+            let coord = |i| f64::from_le_bytes(value(node, i));
+            let mut item = NodeItem::new(coord(0), coord(1), coord(2), coord(3));
+            item.set_offset(u64::from_le_bytes(value(node, 4)));
+            item
+        })
+        .collect();
+    let tree = PackedRTree::build(&leaves, &calc_extent(&leaves), node_size)?;
+
+    let mut fbb = flatbuffers::FlatBufferBuilder::new();
+    let name = header.name().map(|name| fbb.create_string(name));
+    let envelope = header
+        .envelope()
+        .map(|envelope| fbb.create_vector(&envelope.iter().collect::<Vec<_>>()));
+    let header_columns = header.columns().unwrap();
+    let columns: Vec<_> = (0..header_columns.len())
+        .map(|i| {
+            let column = header_columns.get(i);
+            let name = Some(fbb.create_string(column.name()));
+            Column::create(
+                &mut fbb,
+                &ColumnArgs {
+                    name,
+                    type_: column.type_(),
+                    ..Default::default()
+                },
+            )
+        })
+        .collect();
+    let columns = Some(fbb.create_vector(&columns));
+    let new_header = Header::create(
+        &mut fbb,
+        &HeaderArgs {
+            name,
+            envelope,
+            geometry_type: header.geometry_type(),
+            columns,
+            features_count: count as u64,
+            index_node_size: node_size,
+            ..Default::default()
+        },
+    );
+    fbb.finish_size_prefixed(new_header, None);
+
+    let mut out = data[..8].to_vec();
+    out.extend_from_slice(fbb.finished_data());
+    tree.stream_write(&mut out)?;
+    out.extend_from_slice(&data[feature_base..]);
+    Ok(out)
+}
+
+#[test]
+fn select_bbox_node_size() -> Result<()> {
+    let data = countries_with_node_size(4)?;
+    let mut reader = std::io::Cursor::new(&data);
+    let mut fgb = FgbReader::open(&mut reader)?;
+    assert_eq!(fgb.header().index_node_size(), 4);
+    assert_eq!(fgb.select_bbox(8.8, 47.2, 9.5, 55.3)?, 6);
+    let mut names = Vec::new();
+    while let Some(feature) = fgb.next()? {
+        names.push(feature.properties()?["name"].clone());
+    }
+    assert!(names.contains(&"Denmark".to_string()));
+
+    fgb.select_bbox_lazy(8.8, 47.2, 9.5, 55.3)?;
+    let mut lazy_names = Vec::new();
+    while let Some(feature) = fgb.next()? {
+        lazy_names.push(feature.properties()?["name"].clone());
+    }
+    lazy_names.sort();
+    names.sort();
+    assert_eq!(lazy_names, names);
+
+    let async_count = tokio::runtime::Runtime::new()?.block_on(async {
+        let mut fgb = AsyncFgbReader::open(std::io::Cursor::new(&data)).await?;
+        fgb.select_bbox(8.8, 47.2, 9.5, 55.3).await
+    })?;
+    assert_eq!(async_count, 6);
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use byteorder::{ByteOrder, LittleEndian};
+use flatgeobuf::*;
+use geozero::error::Result;
+use std::io::Cursor;
+
+fn countries() -> Vec<u8> {
+    std::fs::read("../../test/data/countries.fgb").unwrap()
+}
+
+/// Offsets of the first leaf node and the first feature
+fn layout(data: &[u8]) -> Result<(usize, usize)> {
+    let header_size = LittleEndian::read_u32(&data[MAGIC_BYTES.len()..]) as usize;
+    let index_base = MAGIC_BYTES.len() + 4 + header_size;
+    let mut cursor = Cursor::new(data);
+    let fgb = FgbReader::open(&mut cursor)?;
+    let num_items = fgb.header().features_count() as usize;
+    let index_size = PackedRTree::index_size(num_items, fgb.header().index_node_size())?;
+    let feature_base = index_base + index_size;
+    let first_leaf = feature_base - num_items * std::mem::size_of::<NodeItem>();
+    Ok((first_leaf, feature_base))
+}
+
+#[test]
+fn verify_valid_file() -> Result<()> {
+    let report = verify(&mut Cursor::new(countries()))?;
+    assert_eq!(report.problems, vec![]);
+    assert!(report.is_valid());
+    assert_eq!(report.features_count, 179);
+    Ok(())
+}
+
+#[test]
+fn verify_magic_bytes() -> Result<()> {
+    let mut data = countries();
+    data[0] = b'x';
+    let report = verify(&mut Cursor::new(data))?;
+    assert_eq!(report.problems, vec![VerifyProblem::MagicBytes]);
+    Ok(())
+}
+
+#[test]
+fn verify_unsupported_version() -> Result<()> {
+    let mut data = countries();
+    data[3] = 4;
+    let report = verify(&mut Cursor::new(data))?;
+    assert_eq!(report.problems, vec![VerifyProblem::Version(4)]);
+    Ok(())
+}
+
+#[test]
+fn verify_truncated_file() -> Result<()> {
+    let mut data = countries();
+    data.truncate(data.len() - 1);
+    let report = verify(&mut Cursor::new(data))?;
+    assert_eq!(report.features_count, 178);
+    assert!(matches!(
+        report.problems[0],
+        VerifyProblem::Truncated {
+            section: "features",
+            ..
+        }
+    ));
+    assert!(report.problems.contains(&VerifyProblem::FeaturesCount {
+        header: 179,
+        actual: 178
+    }));
+    // last leaf points to missing feature
+    assert!(report
+        .problems
+        .iter()
+        .any(|problem| matches!(problem, VerifyProblem::LeafOffset { .. })));
+    Ok(())
+}
+
+#[test]
+fn verify_reports_all_problems() -> Result<()> {
+    let mut data = countries();
+    let (first_leaf, feature_base) = layout(&data)?;
+    // first leaf with empty bounds
+    LittleEndian::write_f64(&mut data[first_leaf..], 1000.0);
+    // second feature with root offset out of bounds
+    let first_size = LittleEndian::read_u32(&data[feature_base..]) as usize;
+    let second = feature_base + 4 + first_size;
+    LittleEndian::write_u32(&mut data[second + 4..], 0xffff_fff0);
+
+    let report = verify(&mut Cursor::new(data))?;
+    assert_eq!(report.features_count, 179);
+    assert_eq!(report.problems.len(), 2);
+    assert_eq!(
+        report.problems[1],
+        VerifyProblem::LeafBounds {
+            leaf: 0,
+            feature: 0
+        }
+    );
+    match &report.problems[0] {
+        VerifyProblem::Feature {
+            feature, offset, ..
+        } => {
+            assert_eq!(*feature, 1);
+            assert_eq!(*offset, 4 + first_size as u64);
+        }
+        problem => panic!("unexpected {:?}", problem),
+    }
+    Ok(())
+}